@@ -0,0 +1,63 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use flat_string::FlatString;
+use std::hint::black_box;
+
+const ASCII: &str = "The quick brown fox jumps over the lazy dog. The quick brown fox jumps over the lazy dog. \
+                     The quick brown fox jumps over the lazy dog. The quick brown fox jumps over the lazy dog. \
+                     The quick brown fox jumps.";
+const UNICODE: &str = "Zăpada albă acoperă dealurile. こんにちは世界! Zăpada albă acoperă dealurile. \
+                       こんにちは世界! Zăpada albă acoperă dealurile. こんにちは世界! Zăpada albă.";
+
+// pops every character, one at a time (like pressing backspace until the text input is empty)
+fn pop_all(c: &mut Criterion, name: &str, text: &str) {
+    let mut group = c.benchmark_group(name);
+    group.bench_function("FlatString::pop", |b| {
+        b.iter_batched(
+            || FlatString::<255>::from_str(text),
+            |mut s| {
+                while let Some(ch) = s.pop() {
+                    black_box(ch);
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("String::pop", |b| {
+        b.iter_batched(
+            || String::from(text),
+            |mut s| {
+                while let Some(ch) = s.pop() {
+                    black_box(ch);
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("FlatString::pop_n", |b| {
+        b.iter_batched(
+            || FlatString::<255>::from_str(text),
+            |mut s| black_box(s.pop_n(50)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("FlatString::truncate_chars", |b| {
+        b.iter_batched(
+            || FlatString::<255>::from_str(text),
+            |mut s| {
+                s.truncate_chars(100);
+                black_box(s)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn bench_pop(c: &mut Criterion) {
+    assert!(ASCII.len() <= 255 && UNICODE.len() <= 255);
+    pop_all(c, "pop_ascii", ASCII);
+    pop_all(c, "pop_unicode", UNICODE);
+}
+
+criterion_group!(benches, bench_pop);
+criterion_main!(benches);
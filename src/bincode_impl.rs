@@ -0,0 +1,36 @@
+use crate::{FlatString, Length};
+use bincode::de::read::Reader;
+use bincode::de::{BorrowDecoder, Decoder};
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{BorrowDecode, Decode, Encode};
+
+/// A FlatString is encoded just like a `String` (the length followed by the bytes of the string), so only `len`
+/// bytes are written, and a FlatString can be decoded from an encoded `String` (and vice versa).
+impl<const SIZE: usize, L: Length> Encode for FlatString<SIZE, L> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.as_str().encode(encoder)
+    }
+}
+
+/// Decodes the string directly in the inline buffer. An error is returned if the string is larger than `SIZE` bytes.
+impl<Context, const SIZE: usize, L: Length> Decode<Context> for FlatString<SIZE, L> {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let len = u64::decode(decoder)?;
+        let len = match usize::try_from(len) {
+            Ok(len) if len <= SIZE => len,
+            _ => return Err(DecodeError::Other("the string is larger than the capacity of the FlatString")),
+        };
+        decoder.claim_bytes_read(len)?;
+        let mut this = Self::new();
+        decoder.reader().read(&mut this.data[..len])?;
+        this.set_from_buffer(len).map_err(|inner| DecodeError::Utf8 { inner })?;
+        Ok(this)
+    }
+}
+
+impl<'de, Context, const SIZE: usize, L: Length> BorrowDecode<'de, Context> for FlatString<SIZE, L> {
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Self::decode(decoder)
+    }
+}
@@ -0,0 +1,29 @@
+use crate::{FlatString, Length};
+use borsh::io::{Error, ErrorKind, Read, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// A FlatString is serialized just like a `String` (the length as a `u32` followed by the bytes of the string), so
+/// only `len` bytes are written.
+impl<const SIZE: usize, L: Length> BorshSerialize for FlatString<SIZE, L> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        self.as_str().serialize(writer)
+    }
+}
+
+/// Reads the string directly in the inline buffer. An error is returned if the string is larger than `SIZE` bytes.
+impl<const SIZE: usize, L: Length> BorshDeserialize for FlatString<SIZE, L> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let len = u32::deserialize_reader(reader)? as usize;
+        if len > SIZE {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "the string is larger than the capacity of the FlatString",
+            ));
+        }
+        let mut this = Self::new();
+        reader.read_exact(&mut this.data[..len])?;
+        this.set_from_buffer(len)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "the string is not valid UTF-8"))?;
+        Ok(this)
+    }
+}
@@ -0,0 +1,124 @@
+use core::fmt;
+
+/// The error returned when a string does not fit in the available space of a FlatString.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError {
+    needed: usize,
+    available: usize,
+}
+impl CapacityError {
+    pub(crate) fn new(needed: usize, available: usize) -> Self {
+        Self { needed, available }
+    }
+
+    /// Returns the number of bytes the operation needed to write.
+    #[inline(always)]
+    pub fn needed(&self) -> usize {
+        self.needed
+    }
+
+    /// Returns the number of bytes that were available in the FlatString when the operation was attempted.
+    #[inline(always)]
+    pub fn available(&self) -> usize {
+        self.available
+    }
+}
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "not enough space in FlatString: {} bytes needed, but only {} bytes available",
+            self.needed, self.available
+        )
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {}
+
+/// The error returned when a byte position can not be used to access a FlatString.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexError {
+    /// The byte position is outside of the string (`len` is the length of the string in bytes).
+    OutOfRange { index: usize, len: usize },
+    /// The byte position is inside the string, but in the middle of a character.
+    NotCharBoundary { index: usize },
+}
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexError::OutOfRange { index, len } => {
+                write!(f, "byte index {} is out of range (the string has {} bytes)", index, len)
+            }
+            IndexError::NotCharBoundary { index } => {
+                write!(f, "byte index {} is not a char boundary", index)
+            }
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for IndexError {}
+
+/// The error returned when a string or a character can not be inserted into a FlatString.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertError {
+    /// The position where the text should be inserted is invalid.
+    Index(IndexError),
+    /// The text does not fit in the available space.
+    Capacity(CapacityError),
+}
+impl From<IndexError> for InsertError {
+    fn from(value: IndexError) -> Self {
+        InsertError::Index(value)
+    }
+}
+impl From<CapacityError> for InsertError {
+    fn from(value: CapacityError) -> Self {
+        InsertError::Capacity(value)
+    }
+}
+impl fmt::Display for InsertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InsertError::Index(e) => e.fmt(f),
+            InsertError::Capacity(e) => e.fmt(f),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for InsertError {}
+
+/// The error returned when a byte buffer does not contain a valid FlatString (see `FlatString::ref_from_bytes`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromBytesError {
+    /// The size of the byte buffer is different from the size of the FlatString.
+    Size { expected: usize, actual: usize },
+    /// The stored length is larger than the capacity of the FlatString.
+    Length { len: usize, capacity: usize },
+    /// The bytes of the string are not valid UTF-8.
+    Utf8,
+    /// The stored number of characters does not match the number of characters of the string.
+    CharsCount { stored: usize, actual: usize },
+    /// The bytes after the end of the string are not 0 (only checked with the `zero-tail` feature).
+    NonZeroTail,
+}
+impl fmt::Display for FromBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FromBytesError::Size { expected, actual } => {
+                write!(f, "expected a buffer of {} bytes, but got {} bytes", expected, actual)
+            }
+            FromBytesError::Length { len, capacity } => {
+                write!(f, "the stored length ({}) is larger than the capacity ({})", len, capacity)
+            }
+            FromBytesError::Utf8 => f.write_str("the string is not valid UTF-8"),
+            FromBytesError::CharsCount { stored, actual } => write!(
+                f,
+                "the stored number of characters ({}) does not match the string ({} characters)",
+                stored, actual
+            ),
+            FromBytesError::NonZeroTail => f.write_str("the bytes after the end of the string are not 0"),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for FromBytesError {}
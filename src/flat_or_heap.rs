@@ -0,0 +1,375 @@
+use crate::{FlatString, IndexError, Length};
+use alloc::string::String;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+#[derive(Clone)]
+enum Repr<const SIZE: usize, L: Length> {
+    Flat(FlatString<SIZE, L>),
+    Heap(String),
+}
+
+/// A string that is stored inline (as a `FlatString<SIZE, L>`) while its content fits in `SIZE` bytes, and is moved
+/// to a heap allocated `String` when it grows past that capacity. Unlike a FlatString, nothing is ever truncated.
+///
+/// Once moved to the heap, the string remains there until it is cleared, set to a value that fits inline, or
+/// explicitly moved back with `shrink_to_inline`.
+///
+/// It has the following methods of a FlatString: `new`, `from_str`, `len`, `is_empty`, `chars_count`,
+/// `capacity`, `as_str`, `clear`, `push_str`, `push`, `set`, `truncate`, `pop`, `insert`, `insert_char`, `remove` and
+/// the `try_insert`, `try_insert_char` and `try_remove` variants that report invalid positions. The methods that only
+/// exist to report or refuse a truncation (`try_push_str`, `try_set`, the `*_with_report` methods, ...) are not needed
+/// here, as every write succeeds. The other methods of `str` are available through `Deref<Target = str>`.
+///
+/// # Example
+/// ```rust
+/// use flat_string::FlatOrHeap;
+/// let mut s = FlatOrHeap::<8>::from_str("Hello");
+/// assert!(s.is_inline());
+/// s.push_str(" World !");
+/// assert!(s.is_heap());
+/// assert_eq!(s.as_str(), "Hello World !");
+/// ```
+#[derive(Clone)]
+pub struct FlatOrHeap<const SIZE: usize = 14, L: Length = u8> {
+    repr: Repr<SIZE, L>,
+}
+impl<const SIZE: usize, L: Length> FlatOrHeap<SIZE, L> {
+    /// Create a new (inline) FlatOrHeap with a fixed inline size
+    pub const fn new() -> Self {
+        Self {
+            repr: Repr::Flat(FlatString::new()),
+        }
+    }
+
+    /// Create a new FlatOrHeap from a string slice. If the string slice is larger than the inline capacity, it will be stored on the heap.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(text: &str) -> Self {
+        let mut this = Self::new();
+        this.push_str(text);
+        this
+    }
+
+    /// Returns true if the content is stored inline (as a FlatString), false otherwise.
+    #[inline(always)]
+    pub fn is_inline(&self) -> bool {
+        matches!(self.repr, Repr::Flat(_))
+    }
+
+    /// Returns true if the content is stored on the heap (as a String), false otherwise.
+    #[inline(always)]
+    pub fn is_heap(&self) -> bool {
+        matches!(self.repr, Repr::Heap(_))
+    }
+
+    /// Returns the inline FlatString, or None if the content is stored on the heap.
+    #[inline(always)]
+    pub fn as_flat(&self) -> Option<&FlatString<SIZE, L>> {
+        match &self.repr {
+            Repr::Flat(s) => Some(s),
+            Repr::Heap(_) => None,
+        }
+    }
+
+    /// Moves the content back to the inline buffer if it fits. Returns true if the content is stored inline after this call.
+    pub fn shrink_to_inline(&mut self) -> bool {
+        if let Repr::Heap(s) = &self.repr {
+            if let Ok(flat) = FlatString::try_from_str(s) {
+                self.repr = Repr::Flat(flat);
+            }
+        }
+        self.is_inline()
+    }
+
+    /// Returns the length of the string in bytes. This operation is performed in O(1) time.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.as_str().len()
+    }
+
+    /// Returns true if the string is empty, false otherwise. This operation is performed in O(1) time.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of characters in the string. This operation is performed in O(1) time while the content is stored inline, and in O(n) time when it is stored on the heap.
+    #[inline(always)]
+    pub fn chars_count(&self) -> usize {
+        match &self.repr {
+            Repr::Flat(s) => s.chars_count(),
+            Repr::Heap(s) => s.chars().count(),
+        }
+    }
+
+    /// Returns the capacity of the string: `SIZE` while the content is stored inline, or the capacity of the heap allocation otherwise.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        match &self.repr {
+            Repr::Flat(s) => s.capacity(),
+            Repr::Heap(s) => s.capacity(),
+        }
+    }
+
+    /// Returns the content as a string slice. This operation is performed in O(1) time.
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        match &self.repr {
+            Repr::Flat(s) => s.as_str(),
+            Repr::Heap(s) => s.as_str(),
+        }
+    }
+
+    /// Clears the content of the string. The content will be stored inline afterwards (the heap allocation, if any, is released).
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.repr = Repr::Flat(FlatString::new());
+    }
+
+    // moves the content to the heap (if needed) and returns the heap allocated string
+    fn spill(&mut self, additional: usize) -> &mut String {
+        if let Repr::Flat(flat) = &self.repr {
+            let mut heap = String::with_capacity(flat.len() + additional);
+            heap.push_str(flat.as_str());
+            self.repr = Repr::Heap(heap);
+        }
+        match &mut self.repr {
+            Repr::Heap(s) => s,
+            Repr::Flat(_) => unreachable!(),
+        }
+    }
+
+    /// Appends a string slice. If the result does not fit in the inline capacity, the content is moved to the heap.
+    pub fn push_str(&mut self, text: &str) {
+        if let Repr::Flat(flat) = &mut self.repr {
+            if flat.try_push_str(text).is_some() {
+                return;
+            }
+        }
+        self.spill(text.len()).push_str(text);
+    }
+
+    /// Appends a character. If the result does not fit in the inline capacity, the content is moved to the heap.
+    #[inline(always)]
+    pub fn push(&mut self, c: char) {
+        let mut bytes = [0; 8];
+        self.push_str(c.encode_utf8(&mut bytes));
+    }
+
+    /// Sets the content to a string slice. The content is stored inline if it fits, and on the heap otherwise.
+    pub fn set(&mut self, text: &str) {
+        match FlatString::try_from_str(text) {
+            Ok(flat) => self.repr = Repr::Flat(flat),
+            Err(_) => {
+                // the heap buffer is reused only if the content is already on the heap
+                if let Repr::Heap(heap) = &mut self.repr {
+                    heap.clear();
+                    heap.push_str(text);
+                } else {
+                    self.repr = Repr::Heap(String::from(text));
+                }
+            }
+        }
+    }
+
+    /// Truncates the string to the specified length (in bytes). The content remains where it is stored.
+    ///
+    /// If new_len is greater than or equal to the string’s current length, this has no effect.
+    ///
+    /// # Panics
+    /// Panics if new_len does not lie on a char boundary.
+    pub fn truncate(&mut self, new_len: usize) {
+        match &mut self.repr {
+            Repr::Flat(s) => s.truncate(new_len),
+            Repr::Heap(s) => s.truncate(new_len),
+        }
+    }
+
+    /// Removes the last character and returns it. Returns None if the string is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        match &mut self.repr {
+            Repr::Flat(s) => s.pop(),
+            Repr::Heap(s) => s.pop(),
+        }
+    }
+
+    /// Inserts a string slice at a byte position. If the result does not fit in the inline capacity, the content is moved to the heap.
+    ///
+    /// # Panics
+    /// Panics if idx is larger than the string’s length, or if it does not lie on a char boundary.
+    pub fn insert(&mut self, idx: usize, text: &str) {
+        if let Repr::Flat(flat) = &mut self.repr {
+            match flat.try_insert(idx, text) {
+                Ok(()) => return,
+                Err(crate::InsertError::Index(e)) => panic!("{}", e),
+                Err(crate::InsertError::Capacity(_)) => {}
+            }
+        }
+        self.spill(text.len()).insert_str(idx, text);
+    }
+
+    /// Inserts a character at a byte position. If the result does not fit in the inline capacity, the content is moved to the heap.
+    ///
+    /// # Panics
+    /// Panics if idx is larger than the string’s length, or if it does not lie on a char boundary.
+    #[inline(always)]
+    pub fn insert_char(&mut self, idx: usize, ch: char) {
+        let mut bytes = [0; 8];
+        self.insert(idx, ch.encode_utf8(&mut bytes));
+    }
+
+    /// Removes a char at a byte position and returns it. The content remains where it is stored.
+    ///
+    /// # Panics
+    /// Panics if idx is larger than or equal to the string’s length, or if it does not lie on a char boundary.
+    pub fn remove(&mut self, idx: usize) -> char {
+        match &mut self.repr {
+            Repr::Flat(s) => s.remove(idx),
+            Repr::Heap(s) => s.remove(idx),
+        }
+    }
+
+    /// Inserts a string slice at a byte position (just like `insert`), or returns an [`IndexError`] if the position is
+    /// out of range or does not lie on a char boundary.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::{FlatOrHeap, IndexError};
+    /// let mut s = FlatOrHeap::<4>::from_str("aă");
+    /// assert_eq!(s.try_insert(2, "b"), Err(IndexError::NotCharBoundary { index: 2 }));
+    /// assert!(s.try_insert(3, " World").is_ok());
+    /// assert_eq!(s.as_str(), "aă World");
+    /// ```
+    pub fn try_insert(&mut self, idx: usize, text: &str) -> Result<(), IndexError> {
+        self.check_index(idx, true)?;
+        self.insert(idx, text);
+        Ok(())
+    }
+
+    /// Inserts a character at a byte position (just like `insert_char`), or returns an [`IndexError`] if the position
+    /// is out of range or does not lie on a char boundary.
+    pub fn try_insert_char(&mut self, idx: usize, ch: char) -> Result<(), IndexError> {
+        self.check_index(idx, true)?;
+        self.insert_char(idx, ch);
+        Ok(())
+    }
+
+    /// Removes a char at a byte position and returns it (just like `remove`), or returns an [`IndexError`] if the
+    /// position is out of range or does not lie on a char boundary.
+    pub fn try_remove(&mut self, idx: usize) -> Result<char, IndexError> {
+        self.check_index(idx, false)?;
+        Ok(self.remove(idx))
+    }
+
+    // checks that idx is a char boundary inside the string (the end of the string is accepted only if allow_end is true)
+    fn check_index(&self, idx: usize, allow_end: bool) -> Result<(), IndexError> {
+        let len = self.len();
+        if idx > len || (idx == len && !allow_end) {
+            return Err(IndexError::OutOfRange { index: idx, len });
+        }
+        if !self.as_str().is_char_boundary(idx) {
+            return Err(IndexError::NotCharBoundary { index: idx });
+        }
+        Ok(())
+    }
+
+    /// Converts the content into a heap allocated String.
+    pub fn into_string(self) -> String {
+        match self.repr {
+            Repr::Flat(s) => String::from(s.as_str()),
+            Repr::Heap(s) => s,
+        }
+    }
+}
+
+impl<const SIZE: usize, L: Length> Deref for FlatOrHeap<SIZE, L> {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const SIZE: usize, L: Length> AsRef<str> for FlatOrHeap<SIZE, L> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const SIZE: usize, L: Length> AsRef<[u8]> for FlatOrHeap<SIZE, L> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const SIZE: usize, L: Length> Borrow<str> for FlatOrHeap<SIZE, L> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const SIZE: usize, L: Length> Default for FlatOrHeap<SIZE, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SIZE: usize, L: Length> From<FlatString<SIZE, L>> for FlatOrHeap<SIZE, L> {
+    fn from(value: FlatString<SIZE, L>) -> Self {
+        Self {
+            repr: Repr::Flat(value),
+        }
+    }
+}
+
+impl<const SIZE: usize, L: Length> From<FlatOrHeap<SIZE, L>> for String {
+    fn from(value: FlatOrHeap<SIZE, L>) -> Self {
+        value.into_string()
+    }
+}
+
+impl<const SIZE: usize, L: Length> core::fmt::Debug for FlatOrHeap<SIZE, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const SIZE: usize, L: Length> core::fmt::Display for FlatOrHeap<SIZE, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<const SIZE: usize, L: Length> core::fmt::Write for FlatOrHeap<SIZE, L> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl<const SIZE: usize, L: Length> PartialEq for FlatOrHeap<SIZE, L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const SIZE: usize, L: Length> Eq for FlatOrHeap<SIZE, L> {}
+
+impl<const SIZE: usize, L: Length> PartialOrd for FlatOrHeap<SIZE, L> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const SIZE: usize, L: Length> Ord for FlatOrHeap<SIZE, L> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const SIZE: usize, L: Length> Hash for FlatOrHeap<SIZE, L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{FlatString, Length};
+use unicode_segmentation::UnicodeSegmentation;
+
+impl<const SIZE: usize, L: Length> FlatString<SIZE, L> {
+    /// Returns the number of extended grapheme clusters (user-perceived characters) in the string.
+    /// Unlike `chars_count`, this operation is performed in O(n) time.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<20>::from_str("e\u{301}👍🏽!");
+    /// assert_eq!(s.chars_count(), 5);
+    /// assert_eq!(s.graphemes_count(), 3);
+    /// ```
+    pub fn graphemes_count(&self) -> usize {
+        self.as_str().graphemes(true).count()
+    }
+
+    /// Appends a string slice to the FlatString. If the string slice is larger than the available space, only the first
+    /// grapheme clusters that fit entirely will be copied (a grapheme cluster, such as a letter followed by a combining
+    /// accent or an emoji sequence, is never split).
+    ///
+    /// The grapheme clusters are computed in the string slice only, the current content of the FlatString is not
+    /// taken into account. If the string slice starts with characters that extend the last grapheme cluster of the
+    /// FlatString (such as combining marks) and they do not fit, they are dropped and the last grapheme cluster of the
+    /// FlatString is left as it was (its base character is not removed).
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<8>::from_str("Hi ");
+    /// s.push_str_graphemes("👍🏽!"); // the emoji (8 bytes) does not fit
+    /// assert_eq!(s.as_str(), "Hi ");
+    /// s.push_str("👍🏽"); // push_str keeps the characters that fit
+    /// assert_eq!(s.as_str(), "Hi 👍");
+    /// ```
+    pub fn push_str_graphemes(&mut self, text: &str) {
+        if self.add_entire_string(text) {
+            return;
+        }
+        let available = SIZE - self.len();
+        let mut end = 0;
+        for (idx, g) in text.grapheme_indices(true) {
+            if idx + g.len() > available {
+                break;
+            }
+            end = idx + g.len();
+        }
+        self.add_entire_string(&text[..end]);
+    }
+
+    /// Removes the last grapheme cluster from the string and returns it. Returns None if the string is empty.
+    ///
+    /// The grapheme cluster is returned as a FlatString of the same size (a copy of the whole `SIZE` bytes buffer,
+    /// even if the grapheme cluster is a single character). When the removed grapheme cluster is not needed, use
+    /// `truncate_graphemes(graphemes_count() - 1)` instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<10>::from_str("cafe\u{301}");
+    /// assert_eq!(s.pop_grapheme().unwrap().as_str(), "e\u{301}");
+    /// assert_eq!(s.as_str(), "caf");
+    /// ```
+    pub fn pop_grapheme(&mut self) -> Option<Self> {
+        let (idx, g) = self.as_str().grapheme_indices(true).next_back()?;
+        let result = Self::from_str(g);
+        self.truncate(idx);
+        Some(result)
+    }
+
+    /// Truncates the string to its first `count` grapheme clusters.
+    ///
+    /// If count is greater than or equal to the number of grapheme clusters in the string, this has no effect.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<20>::from_str("cafe\u{301} au lait");
+    /// s.truncate_graphemes(4);
+    /// assert_eq!(s.as_str(), "cafe\u{301}");
+    /// ```
+    pub fn truncate_graphemes(&mut self, count: usize) {
+        if let Some((idx, _)) = self.as_str().grapheme_indices(true).nth(count) {
+            self.truncate(idx);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{length, FlatString, FromBytesError, Length};
+use core::mem::size_of;
+
+// checks the invariants that `as_str` relies on
+pub(crate) fn validate<const SIZE: usize, L: Length>(
+    data: &[u8; SIZE],
+    len: L::Stored,
+    chars: L::Stored,
+) -> Result<(), FromBytesError> {
+    // every way of reading a FlatString from bytes (including bytemuck and rkyv) goes through here
+    let () = FlatString::<SIZE, L>::SIZE_CHECK;
+    let len = length::to_usize::<L>(len);
+    let chars = length::to_usize::<L>(chars);
+    if len > SIZE {
+        return Err(FromBytesError::Length { len, capacity: SIZE });
+    }
+    let text = core::str::from_utf8(&data[..len]).map_err(|_| FromBytesError::Utf8)?;
+    let actual = text.chars().count();
+    if actual != chars {
+        return Err(FromBytesError::CharsCount { stored: chars, actual });
+    }
+    if cfg!(feature = "zero-tail") && data[len..].iter().any(|b| *b != 0) {
+        return Err(FromBytesError::NonZeroTail);
+    }
+    Ok(())
+}
+
+impl<const SIZE: usize, L: Length> FlatString<SIZE, L> {
+    /// Reinterprets a byte buffer as a FlatString, without copying it.
+    /// The buffer must have exactly `size_of::<FlatString<SIZE, L>>()` bytes, with the layout described in the
+    /// documentation of [`FlatString`]. The content is validated (the length must fit in the capacity, the string must
+    /// be valid UTF-8 and the number of characters must match), otherwise a [`FromBytesError`] is returned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<8>::from_str("Hello");
+    /// let bytes = s.as_raw_bytes().to_vec();
+    /// let r = FlatString::<8>::ref_from_bytes(&bytes).unwrap();
+    /// assert_eq!(r.as_str(), "Hello");
+    ///
+    /// let mut bad = bytes.clone();
+    /// bad[8] = 9; // the length is larger than the capacity
+    /// assert!(FlatString::<8>::ref_from_bytes(&bad).is_err());
+    /// ```
+    ///
+    /// Just like `new`, a FlatString with an invalid `SIZE` can not be read:
+    /// ```rust,compile_fail
+    /// use flat_string::FlatString;
+    /// let r = FlatString::<0>::ref_from_bytes(&[0, 0]);
+    /// ```
+    pub fn ref_from_bytes(bytes: &[u8]) -> Result<&Self, FromBytesError> {
+        let () = Self::SIZE_CHECK;
+        if bytes.len() != size_of::<Self>() {
+            return Err(FromBytesError::Size {
+                expected: size_of::<Self>(),
+                actual: bytes.len(),
+            });
+        }
+        // safety: FlatString is `repr(C)`, has an alignment of 1 and is made only of bytes (any bit pattern is a valid
+        // value for its fields). The invariants used by `as_str` are validated before the reference is returned.
+        let this = unsafe { &*(bytes.as_ptr() as *const Self) };
+        validate::<SIZE, L>(&this.data, this.len, this.chars)?;
+        Ok(this)
+    }
+
+    /// Creates a FlatString by copying it from a byte buffer. The buffer is validated just like in `ref_from_bytes`.
+    pub fn read_from_bytes(bytes: &[u8]) -> Result<Self, FromBytesError> {
+        Self::ref_from_bytes(bytes).copied()
+    }
+
+    /// Returns the raw bytes of the FlatString (the whole buffer, followed by the length and the number of characters),
+    /// with the layout described in the documentation of [`FlatString`].
+    pub fn as_raw_bytes(&self) -> &[u8] {
+        // safety: FlatString is `repr(C)`, is made only of bytes and has no padding
+        unsafe { core::slice::from_raw_parts(self as *const Self as *const u8, size_of::<Self>()) }
+    }
+}
+
+/// The raw (unchecked) representation of a FlatString, with the same layout.
+/// It is used by `bytemuck::checked` to validate a FlatString that is read from a byte buffer.
+#[cfg(feature = "bytemuck")]
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct FlatStringBits<const SIZE: usize, L: Length = u8> {
+    pub data: [u8; SIZE],
+    pub len: L::Stored,
+    pub chars: L::Stored,
+}
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck_impl {
+    use super::{validate, FlatStringBits};
+    use crate::{FlatString, Length};
+    use bytemuck::{CheckedBitPattern, NoUninit, Pod, Zeroable};
+
+    // safety: the fields are u8, [u8; 2] or [u8; 4] (for every length type), so any bit pattern is valid and there is no padding
+    unsafe impl<const SIZE: usize, L: Length> Zeroable for FlatStringBits<SIZE, L> {}
+    unsafe impl<const SIZE: usize, L: Length> Pod for FlatStringBits<SIZE, L> {}
+
+    // safety: a zeroed FlatString is an empty string
+    unsafe impl<const SIZE: usize, L: Length> Zeroable for FlatString<SIZE, L> {
+        fn zeroed() -> Self {
+            FlatString::new()
+        }
+    }
+    // safety: FlatString is `repr(C)`, is made only of bytes and has no padding
+    unsafe impl<const SIZE: usize, L: Length> NoUninit for FlatString<SIZE, L> {}
+    // safety: FlatStringBits has the same layout as FlatString
+    unsafe impl<const SIZE: usize, L: Length> CheckedBitPattern for FlatString<SIZE, L> {
+        type Bits = FlatStringBits<SIZE, L>;
+
+        fn is_valid_bit_pattern(bits: &Self::Bits) -> bool {
+            validate::<SIZE, L>(&bits.data, bits.len, bits.chars).is_ok()
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use core::fmt::Debug;
+use core::hash::Hash;
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+}
+
+/// The type used by a FlatString to store its length (in bytes) and its number of characters.
+/// The length type limits the capacity of the FlatString:
+/// - `u8` (the default) - up to 255 bytes
+/// - `u16` - up to 65535 bytes
+/// - `u32` - up to 4294967295 bytes
+///
+/// The values are stored as little-endian byte arrays, so a FlatString has no alignment requirements and no padding,
+/// regardless of the length type (a `FlatString<SIZE, L>` always takes `SIZE + 2 * size_of::<L>()` bytes).
+///
+/// This trait is sealed and can not be implemented outside of this crate.
+pub trait Length: sealed::Sealed + Copy + 'static {
+    /// The maximum capacity (in bytes) of a FlatString that uses this length type.
+    const MAX: usize;
+    #[doc(hidden)]
+    type Stored: Copy + Eq + Hash + Debug + Send + Sync + 'static;
+    #[doc(hidden)]
+    const ZERO: Self::Stored;
+}
+impl Length for u8 {
+    const MAX: usize = u8::MAX as usize;
+    type Stored = u8;
+    const ZERO: Self::Stored = 0;
+}
+impl Length for u16 {
+    const MAX: usize = u16::MAX as usize;
+    type Stored = [u8; 2];
+    const ZERO: Self::Stored = [0; 2];
+}
+impl Length for u32 {
+    const MAX: usize = if usize::BITS < 32 { usize::MAX } else { u32::MAX as usize };
+    type Stored = [u8; 4];
+    const ZERO: Self::Stored = [0; 4];
+}
+
+// Trait methods can not be called from a `const fn`, so the conversions are done based on the size of the
+// stored value. This is sound because the trait is sealed (the stored value can only be u8, [u8; 2] or [u8; 4]).
+#[inline(always)]
+pub(crate) const fn to_usize<L: Length>(value: L::Stored) -> usize {
+    let ptr = &value as *const L::Stored;
+    unsafe {
+        match core::mem::size_of::<L::Stored>() {
+            1 => *(ptr as *const u8) as usize,
+            2 => u16::from_le((ptr as *const u16).read_unaligned()) as usize,
+            _ => u32::from_le((ptr as *const u32).read_unaligned()) as usize,
+        }
+    }
+}
+
+#[inline(always)]
+pub(crate) const fn from_usize<L: Length>(value: usize) -> L::Stored {
+    let mut result = L::ZERO;
+    let ptr = &mut result as *mut L::Stored;
+    unsafe {
+        match core::mem::size_of::<L::Stored>() {
+            1 => *(ptr as *mut u8) = value as u8,
+            2 => (ptr as *mut u16).write_unaligned((value as u16).to_le()),
+            _ => (ptr as *mut u32).write_unaligned((value as u32).to_le()),
+        }
+    }
+    result
+}
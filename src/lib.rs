@@ -1,99 +1,319 @@
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+#[cfg(feature = "bincode")]
+mod bincode_impl;
+#[cfg(feature = "borsh")]
+mod borsh_impl;
+mod error;
+#[cfg(feature = "alloc")]
+mod flat_or_heap;
+#[cfg(feature = "unicode-segmentation")]
+mod graphemes;
+mod layout;
+mod length;
+#[cfg(feature = "postcard")]
+mod postcard_impl;
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+#[cfg(feature = "serde")]
+mod serde_impl;
+// the original tests compare booleans with assert_eq! and clone Copy values on purpose
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::clone_on_copy)]
 mod tests;
+mod truncation;
+#[cfg(feature = "unicode-width")]
+mod width;
+
+pub use error::{CapacityError, FromBytesError, IndexError, InsertError};
+#[cfg(feature = "alloc")]
+pub use flat_or_heap::FlatOrHeap;
+#[cfg(feature = "bytemuck")]
+pub use layout::FlatStringBits;
+pub use length::Length;
+#[cfg(feature = "serde")]
+pub use serde_impl::serde_truncate;
+pub use truncation::Truncation;
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, DerefMut};
 
-use std::ops::Deref;
+/// Creates a FlatString from a string literal (or any `&str` constant expression) at compile time.
+/// The size of the FlatString is the smallest one the text fits in (the length of the text in bytes).
+///
+/// # Example
+/// ```rust
+/// use flat_string::{flat_str, FlatString};
+/// const GREETING: FlatString<5> = flat_str!("Hello");
+/// let s = flat_str!("こんにちは");
+/// assert_eq!(s.capacity(), 15);
+/// assert_eq!(s.chars_count(), 5);
+/// ```
+#[macro_export]
+macro_rules! flat_str {
+    ($text:expr) => {{
+        const TEXT: &str = $text;
+        const VALUE: $crate::FlatString<{ if TEXT.is_empty() { 1 } else { TEXT.len() } }> =
+            $crate::FlatString::from_str(TEXT);
+        VALUE
+    }};
+}
+
+/// Creates a FlatString using interpolation of runtime expressions (the same syntax as `format!`), without any heap allocation.
+/// The size of the FlatString can be given as the first parameter (followed by `;`) or inferred from the context.
+/// If the formatted text is larger than the available space, only the first characters that fit will be kept (just like `from_str` does).
+///
+/// # Example
+/// ```rust
+/// use flat_string::{flat_format, FlatString};
+/// let s = flat_format!(16; "{}-{:04}", "id", 42);
+/// assert_eq!(s.as_str(), "id-0042");
+///
+/// let s: FlatString<5> = flat_format!("{} {}", "Hello", "World");
+/// assert_eq!(s.as_str(), "Hello");
+/// ```
+#[macro_export]
+macro_rules! flat_format {
+    ($size:expr; $($arg:tt)*) => {{
+        let s: $crate::FlatString<{ $size }> = $crate::flat_format!($($arg)*);
+        s
+    }};
+    ($($arg:tt)*) => {{
+        let mut s = $crate::FlatString::new();
+        let _ = ::core::fmt::Write::write_fmt(&mut s, ::core::format_args!($($arg)*));
+        s
+    }};
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct FlatString<const SIZE: usize = 14> {
+/// A string stored in a fixed-size buffer of `SIZE` bytes, that can be created directly on the stack.
+///
+/// # Layout
+/// A FlatString is `#[repr(C)]`, has an alignment of 1 and no padding. Its layout is stable:
+/// - `SIZE` bytes - the buffer (the string is stored at its beginning, as UTF-8)
+/// - `size_of::<L>()` bytes - the length of the string in bytes (little-endian)
+/// - `size_of::<L>()` bytes - the number of characters of the string (little-endian)
+///
+/// The number of characters is cached (so that `chars_count` is O(1)) and is kept up to date by every method. This is
+/// a logic invariant that unsafe code can break, by changing the number of characters through `as_mut_str`/`DerefMut`
+/// and `str::as_bytes_mut` (see [`FlatString::as_mut_str`]).
+///
+/// A FlatString can be read back from a byte buffer (e.g. a received network packet or a memory mapped file) with
+/// `ref_from_bytes` / `read_from_bytes`, that validate the content before using it.
+///
+/// With the `zerocopy` feature, a FlatString implements `IntoBytes`, `Immutable` and `KnownLayout`, so this support is
+/// write-only: zerocopy's `TryFromBytes` can not be implemented with a custom validation, and deriving it would accept
+/// invalid strings. To read a FlatString that is a field of a zerocopy message, use `ref_from_bytes` on its bytes.
+#[derive(Clone, Copy)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(zerocopy::IntoBytes, zerocopy::Immutable, zerocopy::KnownLayout)
+)]
+#[repr(C)]
+pub struct FlatString<const SIZE: usize = 14, L: Length = u8> {
     data: [u8; SIZE],
-    len: u8,
-    chars: u8,
+    len: L::Stored,
+    chars: L::Stored,
 }
-impl<const SIZE: usize> FlatString<SIZE> {
+impl<const SIZE: usize, L: Length> FlatString<SIZE, L> {
+    // evaluated when a FlatString<SIZE, L> is created, so an invalid SIZE is reported at compile time
+    const SIZE_CHECK: () = {
+        assert!(SIZE > 0, "SIZE must be greater than 0");
+        assert!(
+            SIZE <= L::MAX,
+            "SIZE is too large for the length type (it must be less than 256 for u8 and less than 65536 for u16)"
+        );
+    };
+
     /// Create a new FlatString with a fixed size
     ///
-    /// # Panics
-    /// - If SIZE is 0 or greater than 255
+    /// # Compile errors
+    /// - If SIZE is 0 or greater than the maximum capacity of the length type `L` (255 for the default `u8`), the code that creates the FlatString will not compile
+    ///
+    /// ```rust,compile_fail
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<300>::new(); // error: SIZE is too large for the length type
+    /// ```
     ///
     /// # Example
     /// ```rust
     /// use flat_string::FlatString;
     /// let s = FlatString::<10>::new();
+    /// // use u16 for the length to store more than 255 bytes
+    /// let s = FlatString::<1024, u16>::new();
     /// ```
-    pub fn new() -> Self {
-        assert!(SIZE > 0, "SIZE must be greater than 0");
-        assert!(SIZE < 256, "SIZE must be less than 256");
+    pub const fn new() -> Self {
+        let () = Self::SIZE_CHECK;
         Self {
             data: [0; SIZE],
-            len: 0,
-            chars: 0,
+            len: L::ZERO,
+            chars: L::ZERO,
         }
     }
 
     /// Create a new FlatString from a string slice
     /// If the string slice is larger than the available space, only the first characters that fit will be copied
+    /// This function can be used in a `const` context.
     ///
-    /// # Panics
-    /// - If SIZE is 0 or greater than 255
+    /// # Compile errors
+    /// - If SIZE is 0 or greater than the maximum capacity of the length type `L`
     ///
     /// # Example
     /// ```rust
     /// use flat_string::FlatString;
     /// let s = FlatString::<10>::from_str("Hello");
+    ///
+    /// const NAME: FlatString<16> = FlatString::from_str("default");
+    /// assert_eq!(NAME.as_str(), "default");
     /// ```
-    pub fn from_str(text: &str) -> Self {
+    #[allow(clippy::should_implement_trait)]
+    pub const fn from_str(text: &str) -> Self {
         let mut this = Self::new();
-        this.push_str(text);
+        let bytes = text.as_bytes();
+        let mut len = if bytes.len() > SIZE { SIZE } else { bytes.len() };
+        // move back to the first char boundary (continuation bytes have the form 10xxxxxx)
+        while len < bytes.len() && (bytes[len] & 0xC0) == 0x80 {
+            len -= 1;
+        }
+        let (src, _) = bytes.split_at(len);
+        let (dst, _) = this.data.split_at_mut(len);
+        dst.copy_from_slice(src);
+        let mut chars = 0;
+        let mut i = 0;
+        while i < len {
+            if (bytes[i] & 0xC0) != 0x80 {
+                chars += 1;
+            }
+            i += 1;
+        }
+        this.set_len(len);
+        this.set_chars(chars);
         this
     }
 
+    /// Create a new FlatString from a string slice (just like `from_str`) and returns it together with a [`Truncation`] report that describes how much of the string slice was copied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let (s, report) = FlatString::<10>::from_str_with_report("Hello World !");
+    /// assert_eq!(s.as_str(), "Hello Worl");
+    /// assert_eq!(report.written, 10);
+    /// assert_eq!(report.dropped, 3);
+    /// ```
+    pub fn from_str_with_report(text: &str) -> (Self, Truncation) {
+        let mut this = Self::new();
+        let report = this.push_str_with_report(text);
+        (this, report)
+    }
+
+    /// Create a new FlatString from a string slice, only if the string slice fits in the available space.
+    /// Otherwise a [`CapacityError`] is returned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<10>::try_from_str("Hello").unwrap();
+    /// assert_eq!(s.as_str(), "Hello");
+    /// let e = FlatString::<10>::try_from_str("Hello World !").unwrap_err();
+    /// assert_eq!(e.needed(), 13);
+    /// assert_eq!(e.available(), 10);
+    /// ```
+    pub fn try_from_str(text: &str) -> Result<Self, CapacityError> {
+        let mut this = Self::new();
+        this.try_set(text)?;
+        Ok(this)
+    }
+
     /// Clears the content of the FlatString. This operation does not deallocate the memory, not it does not clear the content o the string. It only resets the length and characters count to 0.
+    /// With the `zero-tail` feature enabled, the content of the buffer is also set to 0.
     #[inline(always)]
     pub fn clear(&mut self) {
-        self.len = 0;
-        self.chars = 0;
+        self.len = L::ZERO;
+        self.chars = L::ZERO;
+        self.zero_tail();
+    }
+
+    // with the `zero-tail` feature, the bytes after the end of the string are set to 0 after every operation that shrinks
+    // or shifts the string, so that the content of the whole buffer only depends on the string
+    #[inline(always)]
+    fn zero_tail(&mut self) {
+        #[cfg(feature = "zero-tail")]
+        {
+            let len = self.len();
+            self.data[len..].fill(0);
+        }
     }
 
     /// Returns the length of the string in bytes. This operation is performed in O(1) time.
     #[inline(always)]
-    pub fn len(&self) -> usize {
-        self.len as usize
+    pub const fn len(&self) -> usize {
+        length::to_usize::<L>(self.len)
     }
 
     /// Returns true if the string is empty, false otherwise. This operation is performed in O(1) time.
     #[inline(always)]
-    pub fn is_empty(&self) -> bool {
-        self.len == 0
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     /// Returns the number of characters in the string. This operation is performed in O(1) time.
     #[inline(always)]
-    pub fn chars_count(&self) -> usize {
-        self.chars as usize
+    pub const fn chars_count(&self) -> usize {
+        length::to_usize::<L>(self.chars)
+    }
+
+    #[inline(always)]
+    const fn set_len(&mut self, len: usize) {
+        self.len = length::from_usize::<L>(len);
+    }
+
+    #[inline(always)]
+    const fn set_chars(&mut self, chars: usize) {
+        self.chars = length::from_usize::<L>(chars);
+    }
+
+    // used by the binary codecs that decode a string directly in the buffer: `data[..len]` already holds the bytes of
+    // the string (len must not be larger than SIZE), and only the length and the number of characters are updated
+    #[cfg(any(feature = "bincode", feature = "borsh"))]
+    fn set_from_buffer(&mut self, len: usize) -> Result<(), core::str::Utf8Error> {
+        let text = core::str::from_utf8(&self.data[..len])?;
+        let chars = text.chars().count();
+        self.set_len(len);
+        self.set_chars(chars);
+        Ok(())
     }
 
     /// Returns the capacity of the FlatString. This operation is performed in O(1) time.
     #[inline(always)]
-    pub fn capacity(&self) -> usize {
+    pub const fn capacity(&self) -> usize {
         SIZE
     }
     // copy the string only if it fits the available space or return false otherwise
     fn add_entire_string(&mut self, text: &str) -> bool {
         let len = text.len();
-        if len + self.len as usize <= SIZE {
-            self.data[self.len as usize..self.len as usize + len].copy_from_slice(text.as_bytes());
-            self.len += len as u8;
-            self.chars += text.chars().count() as u8;
+        let start = self.len();
+        if len + start <= SIZE {
+            self.data[start..start + len].copy_from_slice(text.as_bytes());
+            self.set_len(start + len);
+            self.set_chars(self.chars_count() + text.chars().count());
             true
         } else {
             false
         }
     }
-    // fills the buffer with as much characters as possible
-    fn fill_with_str(&mut self, text: &str) {
+    // fills the buffer with as much characters as possible and returns the number of bytes copied
+    #[allow(clippy::sliced_string_as_bytes)]
+    fn fill_with_str(&mut self, text: &str) -> usize {
         let mut poz = 0;
         let mut count_chars = 0;
-        let len = self.len as usize;
+        let len = self.len();
         for (i, _) in text.char_indices() {
             if i + len > SIZE {
                 break;
@@ -106,8 +326,11 @@ impl<const SIZE: usize> FlatString<SIZE> {
         if count_chars > 1 {
             let bytes = text[..poz].as_bytes();
             self.data[len..len + poz].copy_from_slice(bytes);
-            self.len += poz as u8;
-            self.chars += (count_chars - 1) as u8;
+            self.set_len(len + poz);
+            self.set_chars(self.chars_count() + count_chars - 1);
+            poz
+        } else {
+            0
         }
     }
 
@@ -121,11 +344,61 @@ impl<const SIZE: usize> FlatString<SIZE> {
     /// ```
     #[inline(always)]
     pub fn push_str(&mut self, text: &str) {
+        self.push_str_with_report(text);
+    }
+
+    /// Appends a string slice to the FlatString (just like `push_str`) and returns a [`Truncation`] report that describes how much of the string slice was copied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<8>::from_str("Hello");
+    /// let report = s.push_str_with_report(" World !");
+    /// assert_eq!(s.as_str(), "Hello Wo");
+    /// assert_eq!(report.written, 3);
+    /// assert_eq!(report.dropped, 5);
+    /// assert_eq!(report.remainder(" World !"), "rld !");
+    /// ```
+    #[inline(always)]
+    pub fn push_str_with_report(&mut self, text: &str) -> Truncation {
         // try the fast method first
-        if !self.add_entire_string(text) {
+        let written = if self.add_entire_string(text) {
+            text.len()
+        } else {
             // if it fails, copy as much characters as possible
-            self.fill_with_str(text);
+            self.fill_with_str(text)
+        };
+        Truncation::new(text.len(), written)
+    }
+
+    /// Appends a string slice to the FlatString. If the string slice does not fit in the available space, it is cut at a
+    /// char boundary so that a marker (such as `"…"` or `"..."`) can be appended after it, and the result fits in the
+    /// capacity. If there is not enough room even for the marker, only the characters of the marker that fit are appended.
+    /// The returned [`Truncation`] report describes how much of the string slice (without the marker) was copied.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<12>::from_str("Name: ");
+    /// let report = s.push_str_with_ellipsis("Alexandra", "…");
+    /// assert_eq!(s.as_str(), "Name: Ale…");
+    /// assert_eq!(report.written, 3);
+    /// assert!(report.is_truncated());
+    /// ```
+    pub fn push_str_with_ellipsis(&mut self, text: &str, marker: &str) -> Truncation {
+        if self.add_entire_string(text) {
+            return Truncation::new(text.len(), text.len());
         }
+        let written = if self.len() + marker.len() <= SIZE {
+            let (no_bytes, _) = self.walk_string(text, self.len(), SIZE - marker.len());
+            self.add_entire_string(&text[..no_bytes]);
+            self.add_entire_string(marker);
+            no_bytes
+        } else {
+            self.fill_with_str(marker);
+            0
+        };
+        Truncation::new(text.len(), written)
     }
 
     /// Appends a character to the FlatString. If the character does not fit in the available space, it will not be copied.
@@ -198,6 +471,141 @@ impl<const SIZE: usize> FlatString<SIZE> {
         self.push_str(text);
     }
 
+    /// Sets the content of the FlatString to a string slice (just like `set`) and returns a [`Truncation`] report that describes how much of the string slice was copied.
+    #[inline(always)]
+    pub fn set_with_report(&mut self, text: &str) -> Truncation {
+        self.clear();
+        self.push_str_with_report(text)
+    }
+
+    /// Sets the content of the FlatString to a string slice. If the string slice does not fit, it is cut and a marker
+    /// (such as `"…"` or `"..."`) is appended, just like `push_str_with_ellipsis` does.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<8>::new();
+    /// s.set_with_ellipsis("Hello", "...");
+    /// assert_eq!(s.as_str(), "Hello");
+    /// s.set_with_ellipsis("Hello World !", "...");
+    /// assert_eq!(s.as_str(), "Hello...");
+    /// ```
+    pub fn set_with_ellipsis(&mut self, text: &str, marker: &str) -> Truncation {
+        self.clear();
+        self.push_str_with_ellipsis(text, marker)
+    }
+
+    /// Sets the content of the FlatString to a string slice, only if the string slice fits in the available space.
+    /// Otherwise a [`CapacityError`] is returned and the string remains unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<5>::from_str("Hi");
+    /// assert!(s.try_set("Hello").is_ok());
+    /// assert!(s.try_set("World !").is_err());
+    /// assert_eq!(s.as_str(), "Hello");
+    /// ```
+    pub fn try_set(&mut self, text: &str) -> Result<(), CapacityError> {
+        if text.len() > SIZE {
+            return Err(CapacityError::new(text.len(), SIZE));
+        }
+        self.clear();
+        self.add_entire_string(text);
+        Ok(())
+    }
+
+    /// Creates a FlatString from a string slice and fills the rest of the buffer with a character (the text is
+    /// aligned to the left). With a one byte (ASCII) fill character the whole buffer is used, which is useful for
+    /// the fields of fixed-width records. If the string slice is larger than the capacity, it is truncated just like
+    /// `from_str` does.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<8>::from_str_padded("abc", ' ');
+    /// assert_eq!(s.as_str(), "abc     ");
+    /// assert_eq!(s.len(), 8);
+    /// ```
+    pub fn from_str_padded(text: &str, fill: char) -> Self {
+        let mut this = Self::from_str(text);
+        this.pad(usize::MAX, fill, false, true);
+        this
+    }
+
+    /// Adds fill characters at the beginning of the string until it has `width` characters (aligning the text to the
+    /// right). If the string already has `width` characters or more, this has no effect. The string never grows past
+    /// its capacity (only the fill characters that fit are added).
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<10>::from_str("42");
+    /// s.pad_left(5, '0');
+    /// assert_eq!(s.as_str(), "00042");
+    /// ```
+    pub fn pad_left(&mut self, width: usize, fill: char) {
+        self.pad(width.saturating_sub(self.chars_count()), fill, true, false);
+    }
+
+    /// Adds fill characters at the end of the string until it has `width` characters (aligning the text to the left).
+    /// If the string already has `width` characters or more, this has no effect. The string never grows past its
+    /// capacity (only the fill characters that fit are added).
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<10>::from_str("ab");
+    /// s.pad_right(5, '.');
+    /// assert_eq!(s.as_str(), "ab...");
+    /// ```
+    pub fn pad_right(&mut self, width: usize, fill: char) {
+        self.pad(width.saturating_sub(self.chars_count()), fill, false, true);
+    }
+
+    /// Adds fill characters on both sides of the string until it has `width` characters (centering the text). When
+    /// the number of fill characters is odd, the extra one is added at the end (just like `format!("{:^width$}")`).
+    /// The string never grows past its capacity (only the fill characters that fit are added).
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<10>::from_str("ab");
+    /// s.center(7, '*');
+    /// assert_eq!(s.as_str(), "**ab***");
+    /// ```
+    pub fn center(&mut self, width: usize, fill: char) {
+        self.pad(width.saturating_sub(self.chars_count()), fill, true, true);
+    }
+
+    // adds `count` fill characters (or as many as fit in the available space) before the string, after it, or split
+    // between both sides (when both `before` and `after` are true)
+    fn pad(&mut self, count: usize, fill: char, before: bool, after: bool) {
+        let mut bytes = [0; 4];
+        let fill = fill.encode_utf8(&mut bytes).as_bytes();
+        let len = self.len();
+        let count = count.min((SIZE - len) / fill.len());
+        if count == 0 {
+            return;
+        }
+        let left = match (before, after) {
+            (true, true) => count / 2,
+            (true, false) => count,
+            _ => 0,
+        };
+        let shift = left * fill.len();
+        self.data.copy_within(0..len, shift);
+        for chunk in self.data[..shift].chunks_exact_mut(fill.len()) {
+            chunk.copy_from_slice(fill);
+        }
+        let end = len + count * fill.len();
+        for chunk in self.data[len + shift..end].chunks_exact_mut(fill.len()) {
+            chunk.copy_from_slice(fill);
+        }
+        self.set_len(end);
+        self.set_chars(self.chars_count() + count);
+    }
+
     /// Returns the content of the FlatString as a string slice. This operation is performed in O(1) time.
     ///
     /// # Example
@@ -207,12 +615,39 @@ impl<const SIZE: usize> FlatString<SIZE> {
     /// assert_eq!(s.as_str(), "Hello");
     /// ```
     #[inline(always)]
-    pub fn as_str(&self) -> &str {
-        if self.len == 0 {
-            ""
-        } else {
-            unsafe { std::str::from_utf8_unchecked(&self.data[..self.len as usize]) }
-        }
+    pub const fn as_str(&self) -> &str {
+        let (bytes, _) = self.data.split_at(self.len());
+        // safety: data[..len] always contains valid UTF-8
+        unsafe { core::str::from_utf8_unchecked(bytes) }
+    }
+
+    /// Returns the content of the FlatString as a mutable string slice. This operation is performed in O(1) time.
+    ///
+    /// The length of a `&mut str` can not be changed, and the safe in-place operations of `str` (such as
+    /// `make_ascii_uppercase`) do not change the number of characters either, so the cached length and number of
+    /// characters remain correct.
+    ///
+    /// Unsafe code that edits the bytes (`str::as_bytes_mut`) only has to keep them valid UTF-8, but changing the
+    /// number of characters that way (e.g. by replacing "ab" with "ä") breaks a logic invariant of the FlatString: the
+    /// cached number of characters is not updated. Until the content is set again (e.g. with `set` or `clear`),
+    /// `chars_count` is wrong and so are the results of the methods that rely on it (the character index methods,
+    /// `pop_n`, `truncate_chars`, the padding methods, ...). The string remains valid UTF-8 and these methods do not
+    /// panic because of it, but the number of characters is only guaranteed to be correct again after a new content is
+    /// set.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<10>::from_str("Hello");
+    /// s.as_mut_str().make_ascii_uppercase();
+    /// assert_eq!(s.as_str(), "HELLO");
+    /// ```
+    #[inline(always)]
+    pub const fn as_mut_str(&mut self) -> &mut str {
+        let len = self.len();
+        let (bytes, _) = self.data.split_at_mut(len);
+        // safety: data[..len] always contains valid UTF-8
+        unsafe { core::str::from_utf8_unchecked_mut(bytes) }
     }
 
     /// Truncates this FlatString to the specified length.
@@ -222,26 +657,210 @@ impl<const SIZE: usize> FlatString<SIZE> {
     /// # Panics
     /// Panics if new_len does not lie on a char boundary.
     pub fn truncate(&mut self, new_len: usize) {
-        if new_len >= self.len as usize {
+        if new_len >= self.len() {
             return;
         }
         let p = &(self.as_str())[..new_len];
-        self.chars = p.chars().count() as u8;
-        self.len = new_len as u8;
+        self.set_chars(p.chars().count());
+        self.set_len(new_len);
+        self.zero_tail();
     }
 
     /// Removes the last character from the string buffer and returns it.
     /// Returns None if this String is empty.
+    ///
+    /// The character is decoded backward from the end of the string, so this operation is performed in O(1) time.
     pub fn pop(&mut self) -> Option<char> {
-        if self.chars > 0 {
-            if let Some(ch) = self.chars().last() {
-                assert!(self.len >= ch.len_utf8() as u8);
-                self.chars -= 1;
-                self.len -= ch.len_utf8() as u8;
-                return Some(ch);
-            }
+        let ch = self.as_str().chars().next_back()?;
+        self.set_len(self.len() - ch.len_utf8());
+        self.set_chars(self.chars_count().saturating_sub(1));
+        self.zero_tail();
+        Some(ch)
+    }
+
+    /// Removes the last `n` characters from the string and returns the number of characters that were removed
+    /// (less than `n` if the string has fewer characters). Only the removed characters are visited.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<16>::from_str("zăpadă");
+    /// assert_eq!(s.pop_n(2), 2);
+    /// assert_eq!(s.as_str(), "zăpa");
+    /// assert_eq!(s.pop_n(10), 4);
+    /// assert!(s.is_empty());
+    /// ```
+    pub fn pop_n(&mut self, n: usize) -> usize {
+        let n = n.min(self.chars_count());
+        self.remove_last_chars(n);
+        n
+    }
+
+    /// Truncates the string to its first `count` characters.
+    ///
+    /// If count is greater than or equal to the number of characters in the string, this has no effect.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<16>::from_str("zăpadă");
+    /// s.truncate_chars(3);
+    /// assert_eq!(s.as_str(), "zăp");
+    /// ```
+    pub fn truncate_chars(&mut self, count: usize) {
+        if count >= self.chars_count() {
+            return;
+        }
+        if let Some(idx) = self.char_to_byte(count) {
+            debug_assert!(self.as_str().is_char_boundary(idx));
+            self.set_len(idx);
+            self.set_chars(count);
+            self.zero_tail();
+        }
+    }
+
+    // removes the last `n` characters (n must not be larger than the number of characters), walking the string backward
+    fn remove_last_chars(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let len = self.len();
+        let new_len = if len == self.chars_count() && self.as_str().is_char_boundary(len - n) {
+            // ASCII only (every character is one byte long)
+            len - n
+        } else {
+            self.as_str().char_indices().nth_back(n - 1).map_or(0, |(idx, _)| idx)
+        };
+        self.set_len(new_len);
+        self.set_chars(self.chars_count().saturating_sub(n));
+        self.zero_tail();
+    }
+
+    /// Returns the byte position of the character with the index `char_idx`, or the length of the string if `char_idx`
+    /// is equal to the number of characters (the position after the last character). Returns None if `char_idx` is
+    /// larger than the number of characters.
+    ///
+    /// For ASCII strings this operation is performed in O(1) time. Otherwise the string is walked from the closest end.
+    /// The returned position is always a char boundary.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<16>::from_str("aăb");
+    /// assert_eq!(s.char_to_byte(2), Some(3));
+    /// assert_eq!(s.char_to_byte(3), Some(4));
+    /// assert_eq!(s.char_to_byte(4), None);
+    /// ```
+    pub fn char_to_byte(&self, char_idx: usize) -> Option<usize> {
+        let chars = self.chars_count();
+        if char_idx > chars {
+            return None;
+        }
+        // ASCII only (every character is one byte long). The position is checked, so that the result is always a char
+        // boundary (even if the number of characters was made stale by editing the bytes through `as_bytes_mut`).
+        if self.len() == chars && self.as_str().is_char_boundary(char_idx) {
+            return Some(char_idx);
+        }
+        if char_idx == chars {
+            return Some(self.len());
+        }
+        if char_idx <= chars - char_idx {
+            self.as_str().char_indices().nth(char_idx).map(|(idx, _)| idx)
+        } else {
+            self.as_str().char_indices().nth_back(chars - char_idx - 1).map(|(idx, _)| idx)
+        }
+    }
+
+    /// Returns the index of the character that starts at the byte position `byte_idx` (the number of characters before
+    /// it). Returns None if `byte_idx` is larger than the length of the string or does not lie on a char boundary.
+    ///
+    /// For ASCII strings this operation is performed in O(1) time. Otherwise the string is walked from the closest end.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<16>::from_str("aăb");
+    /// assert_eq!(s.byte_to_char(3), Some(2));
+    /// assert_eq!(s.byte_to_char(2), None);
+    /// ```
+    pub fn byte_to_char(&self, byte_idx: usize) -> Option<usize> {
+        let len = self.len();
+        if byte_idx > len || !self.as_str().is_char_boundary(byte_idx) {
+            return None;
+        }
+        if len == self.chars_count() {
+            return Some(byte_idx);
+        }
+        if byte_idx <= len - byte_idx {
+            Some(self.as_str()[..byte_idx].chars().count())
+        } else {
+            Some(self.chars_count().saturating_sub(self.as_str()[byte_idx..].chars().count()))
+        }
+    }
+
+    /// Returns the character with the index `char_idx`, or None if `char_idx` is not smaller than the number of characters.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<16>::from_str("aăb");
+    /// assert_eq!(s.char_at(1), Some('ă'));
+    /// assert_eq!(s.char_at(3), None);
+    /// ```
+    pub fn char_at(&self, char_idx: usize) -> Option<char> {
+        if char_idx >= self.chars_count() {
+            return None;
+        }
+        let idx = self.char_to_byte(char_idx)?;
+        self.as_str()[idx..].chars().next()
+    }
+
+    /// Inserts a string slice into this FlatString at a character position (just like `insert` does for a byte
+    /// position). If the resulted string does not fit in the capacity, the string is truncated (only whole characters
+    /// are kept).
+    ///
+    /// # Panics
+    /// Panics if char_idx is larger than the number of characters.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<16>::from_str("zăpdă");
+    /// s.insert_at_char(3, "a");
+    /// assert_eq!(s.as_str(), "zăpadă");
+    /// ```
+    pub fn insert_at_char(&mut self, char_idx: usize, text: &str) {
+        match self.char_to_byte(char_idx) {
+            Some(idx) => self.insert(idx, text),
+            None => panic!(
+                "char index {} is out of range (the string has {} characters)",
+                char_idx,
+                self.chars_count()
+            ),
+        }
+    }
+
+    /// Removes the character at a character position and returns it (just like `remove` does for a byte position).
+    ///
+    /// # Panics
+    /// Panics if char_idx is larger than or equal to the number of characters.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<16>::from_str("zăpadă");
+    /// assert_eq!(s.remove_at_char(1), 'ă');
+    /// assert_eq!(s.as_str(), "zpadă");
+    /// ```
+    pub fn remove_at_char(&mut self, char_idx: usize) -> char {
+        match self.char_to_byte(char_idx) {
+            Some(idx) if char_idx < self.chars_count() => self.remove(idx),
+            _ => panic!(
+                "char index {} is out of range (the string has {} characters)",
+                char_idx,
+                self.chars_count()
+            ),
         }
-        None
     }
 
     /// Inserts a string slice into this FlatString at a byte position.
@@ -254,35 +873,67 @@ impl<const SIZE: usize> FlatString<SIZE> {
         self.insert_fast(index, text);
     }
 
-    fn insert_fast(&mut self, index: usize, text: &str) {
+    /// Inserts a string slice into this FlatString at a byte position (just like `insert`) and returns a [`Truncation`] report that describes how much of the string slice was copied.
+    /// The characters of the existing string that are shifted beyond the capacity are dropped (and are not part of the report).
+    ///
+    /// #Panics
+    ///
+    /// Panics if idx is larger than the FlatString’s length, or if it does not lie on a char boundary.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<6>::from_str("ab");
+    /// let report = s.insert_with_report(1, "こん");
+    /// assert_eq!(s.as_str(), "aこ");
+    /// assert_eq!(report.written, 3);
+    /// assert_eq!(report.dropped, 3);
+    /// ```
+    pub fn insert_with_report(&mut self, index: usize, text: &str) -> Truncation {
+        let written = self.insert_fast(index, text);
+        Truncation::new(text.len(), written)
+    }
+
+    fn insert_fast(&mut self, index: usize, text: &str) -> usize {
+        if let Err(e) = self.check_index(index, true) {
+            panic!("{}", e);
+        }
         if index + text.len() < SIZE {
             // there is room to shift all or a part of the existing text
             self.rshift(index, text.len());
+        } else {
+            // the existing text is overwritten: if the written part ends inside one of its characters, the rest of
+            // the existing text is dropped
+            let end = index + self.walk_string(text, index, SIZE).0;
+            if end < self.len() && !self.as_str().is_char_boundary(end) {
+                self.set_len(end);
+            }
         }
-        self.write_at(index, text);
-        self.chars = self
-            .walk_string(
-                std::str::from_utf8(&self.data[..self.len as usize]).unwrap(),
-                0,
-                SIZE,
-            )
-            .1 as u8;
+        let written = self.write_at(index, text);
+        self.set_chars(
+            self.walk_string(core::str::from_utf8(&self.data[..self.len()]).unwrap(), 0, SIZE)
+                .1,
+        );
+        self.zero_tail();
+        written
     }
 
-    fn write_at(&mut self, index: usize, text: &str) {
+    #[allow(clippy::sliced_string_as_bytes)]
+    fn write_at(&mut self, index: usize, text: &str) -> usize {
         let (poz, count_chars) = self.walk_string(text, index, SIZE);
 
         if count_chars > 0 {
             let bytes = text[..poz].as_bytes();
             self.data[index..index + poz].copy_from_slice(bytes);
             // increase len if the written text exceeds original length
-            self.len = self.len.max((index + poz) as u8);
+            self.set_len(self.len().max(index + poz));
         }
+        poz
     }
 
     fn rshift(&mut self, index: usize, shift_size: usize) {
         let dst_start = index + shift_size;
-        let str_to_shift = std::str::from_utf8(&self.data[index..self.len as usize]).unwrap();
+        let str_to_shift = core::str::from_utf8(&self.data[index..self.len()]).unwrap();
         let max_bytes_to_shift = str_to_shift.len();
 
         let (no_bytes_to_shift, no_chars_to_shift) =
@@ -291,13 +942,12 @@ impl<const SIZE: usize> FlatString<SIZE> {
         if no_chars_to_shift > 0 {
             self.data
                 .copy_within(index..index + no_bytes_to_shift, dst_start);
-
-            // Adjust by the actual number of bytes added or removed.
-            // Removal is possibie when a unicode character cannot be
-            // entirely copied.
-            self.len += (shift_size + no_bytes_to_shift) as u8;
-            self.len -= max_bytes_to_shift as u8;
         }
+        // Adjust by the actual number of bytes added or removed.
+        // Removal is possibie when a unicode character cannot be
+        // entirely copied (even if no character is shifted, the
+        // characters after index are dropped).
+        self.set_len(self.len() + shift_size + no_bytes_to_shift - max_bytes_to_shift);
     }
 
     fn walk_string(&self, text: &str, start_index: usize, max_size: usize) -> (usize, usize) {
@@ -323,45 +973,523 @@ impl<const SIZE: usize> FlatString<SIZE> {
         self.insert(idx, ch.encode_utf8(&mut bytes));
     }
 
+    /// Tries to insert a string slice into this FlatString at a byte position.
+    /// Unlike `insert`, nothing is truncated: if the position is invalid or the resulted string does not fit in the available space, an [`InsertError`] is returned and the string remains unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::{FlatString, InsertError};
+    /// let mut s = FlatString::<12>::from_str("Held");
+    /// assert!(s.try_insert(3, "lo Worl").is_ok());
+    /// assert_eq!(s.as_str(), "Hello World");
+    /// assert!(matches!(s.try_insert(0, "!!"), Err(InsertError::Capacity(_))));
+    /// assert!(matches!(s.try_insert(20, ""), Err(InsertError::Index(_))));
+    /// ```
+    pub fn try_insert(&mut self, idx: usize, text: &str) -> Result<(), InsertError> {
+        self.check_index(idx, true)?;
+        let len = self.len();
+        if len + text.len() > SIZE {
+            return Err(CapacityError::new(text.len(), SIZE - len).into());
+        }
+        self.data.copy_within(idx..len, idx + text.len());
+        self.data[idx..idx + text.len()].copy_from_slice(text.as_bytes());
+        self.set_len(len + text.len());
+        self.set_chars(self.chars_count() + text.chars().count());
+        Ok(())
+    }
+
+    /// Tries to insert a character into this FlatString at a byte position.
+    /// If the position is invalid or the character does not fit in the available space, an [`InsertError`] is returned and the string remains unchanged.
+    pub fn try_insert_char(&mut self, idx: usize, ch: char) -> Result<(), InsertError> {
+        let mut bytes = [0; 8];
+        self.try_insert(idx, ch.encode_utf8(&mut bytes))
+    }
+
     /// Removes a char from this FlatString at a byte position and returns it.
     ///
     /// # Panics
     ///
     /// Panics if idx is larger than or equal to the FlatString’s length, or if it does not lie on a char boundary.
     pub fn remove(&mut self, idx: usize) -> char {
-        assert!(idx < self.len as usize);
+        match self.try_remove(idx) {
+            Ok(ch) => ch,
+            Err(e) => panic!("{}", e),
+        }
+    }
 
-        let conv = std::str::from_utf8(&self.data[idx..]);
-        assert!(conv.is_ok());
-        let ch_opt = conv.unwrap().chars().next();
-        assert!(ch_opt.is_some());
-        let ch = ch_opt.unwrap();
+    /// Tries to remove a char from this FlatString at a byte position and returns it.
+    /// If idx is larger than or equal to the FlatString’s length, or if it does not lie on a char boundary, an [`IndexError`] is returned and the string remains unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::{FlatString, IndexError};
+    /// let mut s = FlatString::<10>::from_str("aんb");
+    /// assert_eq!(s.try_remove(2), Err(IndexError::NotCharBoundary { index: 2 }));
+    /// assert_eq!(s.try_remove(1), Ok('ん'));
+    /// assert_eq!(s.try_remove(2), Err(IndexError::OutOfRange { index: 2, len: 2 }));
+    /// ```
+    pub fn try_remove(&mut self, idx: usize) -> Result<char, IndexError> {
+        self.check_index(idx, false)?;
+        let ch = self.as_str()[idx..].chars().next().unwrap();
 
         let next_char_as_byte_index = idx + ch.len_utf8();
         if next_char_as_byte_index < SIZE {
             self.data.copy_within(next_char_as_byte_index.., idx);
         }
-        self.len -= ch.len_utf8() as u8;
-        self.chars -= 1;
-        ch
+        self.set_len(self.len() - ch.len_utf8());
+        self.set_chars(self.chars_count().saturating_sub(1));
+        self.zero_tail();
+        Ok(ch)
+    }
+
+    /// Removes the leading and trailing whitespace from the string, in place (the content is moved to the beginning
+    /// of the buffer, no copy of the FlatString is made).
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<16>::from_str("  Hello\t\n");
+    /// s.trim_in_place();
+    /// assert_eq!(s.as_str(), "Hello");
+    /// ```
+    pub fn trim_in_place(&mut self) {
+        self.trim_end_in_place();
+        self.trim_start_in_place();
+    }
+
+    /// Removes the leading whitespace from the string, in place (the content is moved to the beginning of the buffer).
+    pub fn trim_start_in_place(&mut self) {
+        let len = self.len();
+        let start = len - self.as_str().trim_start().len();
+        if start == 0 {
+            return;
+        }
+        let removed = self.as_str()[..start].chars().count();
+        self.data.copy_within(start..len, 0);
+        self.set_len(len - start);
+        self.set_chars(self.chars_count().saturating_sub(removed));
+        self.zero_tail();
+    }
+
+    /// Removes the trailing whitespace from the string, in place.
+    pub fn trim_end_in_place(&mut self) {
+        let new_len = self.as_str().trim_end().len();
+        if new_len == self.len() {
+            return;
+        }
+        let removed = self.as_str()[new_len..].chars().count();
+        self.set_len(new_len);
+        self.set_chars(self.chars_count().saturating_sub(removed));
+        self.zero_tail();
+    }
+
+    /// Retains only the characters for which `f` returns true, in place (the characters that are kept are moved
+    /// towards the beginning of the buffer). The characters are visited in order, exactly once.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<16>::from_str("a1ă2b3");
+    /// s.retain(|c| !c.is_ascii_digit());
+    /// assert_eq!(s.as_str(), "aăb");
+    /// assert_eq!(s.chars_count(), 3);
+    /// ```
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, mut f: F) {
+        // moves the characters that were not visited after the ones that were kept and updates the length, even if
+        // `f` panics (so that the string remains valid)
+        struct Guard<'a, const SIZE: usize, L: Length> {
+            s: &'a mut FlatString<SIZE, L>,
+            len: usize,
+            read: usize,
+            write: usize,
+            removed: usize,
+        }
+        impl<const SIZE: usize, L: Length> Drop for Guard<'_, SIZE, L> {
+            fn drop(&mut self) {
+                self.s.data.copy_within(self.read..self.len, self.write);
+                self.s.set_len(self.write + self.len - self.read);
+                self.s.set_chars(self.s.chars_count().saturating_sub(self.removed));
+                self.s.zero_tail();
+            }
+        }
+
+        let len = self.len();
+        let mut g = Guard {
+            s: self,
+            len,
+            read: 0,
+            write: 0,
+            removed: 0,
+        };
+        while g.read < g.len {
+            let ch = g.s.decode_char(g.read, g.len);
+            let ch_len = ch.len_utf8();
+            if f(ch) {
+                g.s.data.copy_within(g.read..g.read + ch_len, g.write);
+                g.write += ch_len;
+            } else {
+                g.removed += 1;
+            }
+            g.read += ch_len;
+        }
+    }
+
+    /// Replaces every sequence of whitespace characters (spaces, tabs, new lines, ...) with a single space, in place.
+    /// Leading and trailing whitespace is collapsed as well, but not removed (use `trim_in_place` for that).
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<32>::from_str(" Hello \t\n  World ");
+    /// s.collapse_whitespace();
+    /// assert_eq!(s.as_str(), " Hello World ");
+    /// ```
+    pub fn collapse_whitespace(&mut self) {
+        let len = self.len();
+        let mut read = 0;
+        let mut write = 0;
+        let mut removed = 0;
+        let mut in_whitespace = false;
+        while read < len {
+            let ch = self.decode_char(read, len);
+            let ch_len = ch.len_utf8();
+            if !ch.is_whitespace() {
+                self.data.copy_within(read..read + ch_len, write);
+                write += ch_len;
+                in_whitespace = false;
+            } else if in_whitespace {
+                removed += 1;
+            } else {
+                // the space takes one byte, so it never overwrites bytes that were not visited yet
+                self.data[write] = b' ';
+                write += 1;
+                in_whitespace = true;
+            }
+            read += ch_len;
+        }
+        self.set_len(write);
+        self.set_chars(self.chars_count().saturating_sub(removed));
+        self.zero_tail();
+    }
+
+    // maps every character of the string, keeping only the characters of the result that fit in the capacity
+    fn map_chars<I: Iterator<Item = char>>(&self, f: fn(char) -> I) -> Self {
+        let mut result = Self::new();
+        for c in self.as_str().chars().flat_map(f) {
+            if result.try_push(c).is_none() {
+                break;
+            }
+        }
+        result
+    }
+
+    // maps every character of the string only if the result fits in the capacity
+    fn try_map_chars<I: Iterator<Item = char>>(&self, f: fn(char) -> I) -> Result<Self, CapacityError> {
+        let needed: usize = self.as_str().chars().flat_map(f).map(char::len_utf8).sum();
+        if needed > SIZE {
+            return Err(CapacityError::new(needed, SIZE));
+        }
+        Ok(self.map_chars(f))
+    }
+
+    // converts the case of the string in place if every character is mapped to the same number of bytes (returns
+    // false and leaves the string unchanged otherwise, in which case the string has to be rebuilt)
+    fn convert_case_in_place<I: Iterator<Item = char>>(&mut self, ascii: fn(&mut str), f: fn(char) -> I) -> bool {
+        let len = self.len();
+        if len == self.chars_count() {
+            // ASCII only
+            ascii(self.as_mut_str());
+            return true;
+        }
+        if !self.as_str().chars().all(|c| f(c).map(char::len_utf8).sum::<usize>() == c.len_utf8()) {
+            return false;
+        }
+        let mut idx = 0;
+        let mut chars = 0;
+        while idx < len {
+            // the mapping of a character is written over it, so the rest of the string is not modified
+            let c = self.decode_char(idx, len);
+            for mapped in f(c) {
+                idx += mapped.encode_utf8(&mut self.data[idx..]).len();
+                chars += 1;
+            }
+        }
+        self.set_chars(chars);
+        true
+    }
+
+    /// Returns the lowercase equivalent of this FlatString (as a new FlatString of the same size).
+    ///
+    /// Characters are mapped one by one (using `char::to_lowercase`), so a mapping can change the length of the
+    /// string (e.g. 'İ' becomes "i̇"). If the result is larger than the capacity, only the first characters that fit
+    /// are kept (just like `from_str` does). Context dependent mappings (such as the final Greek sigma) are not applied.
+    ///
+    /// This method hides `str::to_lowercase` (that is otherwise reachable through `Deref`). Use
+    /// `as_str().to_lowercase()` to get a `String` instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<16>::from_str("ZĂPADĂ");
+    /// assert_eq!(s.to_lowercase().as_str(), "zăpadă");
+    /// ```
+    pub fn to_lowercase(&self) -> Self {
+        let mut result = *self;
+        result.make_lowercase();
+        result
+    }
+
+    /// Returns the uppercase equivalent of this FlatString (as a new FlatString of the same size).
+    ///
+    /// Characters are mapped one by one (using `char::to_uppercase`), so a mapping can change the length of the
+    /// string (e.g. 'ß' becomes "SS"). If the result is larger than the capacity, only the first characters that fit
+    /// are kept (just like `from_str` does).
+    ///
+    /// This method hides `str::to_uppercase` (that is otherwise reachable through `Deref`). Use
+    /// `as_str().to_uppercase()` to get a `String` instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<16>::from_str("Straße");
+    /// assert_eq!(s.to_uppercase().as_str(), "STRASSE");
+    /// ```
+    pub fn to_uppercase(&self) -> Self {
+        let mut result = *self;
+        result.make_uppercase();
+        result
+    }
+
+    /// Returns the lowercase equivalent of this FlatString (just like `to_lowercase`), only if it fits in the capacity.
+    /// Otherwise a [`CapacityError`] is returned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<4>::from_str("İİ");
+    /// let e = s.try_to_lowercase().unwrap_err();
+    /// assert_eq!(e.needed(), 6);
+    /// assert_eq!(s.to_lowercase().as_str(), "i\u{307}i");
+    /// ```
+    pub fn try_to_lowercase(&self) -> Result<Self, CapacityError> {
+        let mut result = *self;
+        result.try_make_lowercase()?;
+        Ok(result)
+    }
+
+    /// Returns the uppercase equivalent of this FlatString (just like `to_uppercase`), only if it fits in the capacity.
+    /// Otherwise a [`CapacityError`] is returned.
+    pub fn try_to_uppercase(&self) -> Result<Self, CapacityError> {
+        let mut result = *self;
+        result.try_make_uppercase()?;
+        Ok(result)
+    }
+
+    /// Converts this FlatString to its lowercase equivalent (see `to_lowercase`). If the result is larger than the
+    /// capacity, only the first characters that fit are kept.
+    ///
+    /// The conversion is done in place when every character is mapped to the same number of bytes (this is always the
+    /// case for ASCII strings). Otherwise the string is rebuilt from the mapped characters.
+    pub fn make_lowercase(&mut self) {
+        if !self.convert_case_in_place(str::make_ascii_lowercase, char::to_lowercase) {
+            *self = self.map_chars(char::to_lowercase);
+        }
+    }
+
+    /// Converts this FlatString to its uppercase equivalent (see `to_uppercase`). If the result is larger than the
+    /// capacity, only the first characters that fit are kept.
+    ///
+    /// The conversion is done in place when every character is mapped to the same number of bytes (this is always the
+    /// case for ASCII strings). Otherwise the string is rebuilt from the mapped characters.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<16>::from_str("Straße");
+    /// s.make_uppercase();
+    /// assert_eq!(s.as_str(), "STRASSE");
+    /// ```
+    pub fn make_uppercase(&mut self) {
+        if !self.convert_case_in_place(str::make_ascii_uppercase, char::to_uppercase) {
+            *self = self.map_chars(char::to_uppercase);
+        }
+    }
+
+    /// Converts this FlatString to its lowercase equivalent (just like `make_lowercase`), only if the result fits in
+    /// the capacity. Otherwise a [`CapacityError`] is returned and the string remains unchanged.
+    pub fn try_make_lowercase(&mut self) -> Result<(), CapacityError> {
+        if !self.convert_case_in_place(str::make_ascii_lowercase, char::to_lowercase) {
+            *self = self.try_map_chars(char::to_lowercase)?;
+        }
+        Ok(())
+    }
+
+    /// Converts this FlatString to its uppercase equivalent (just like `make_uppercase`), only if the result fits in
+    /// the capacity. Otherwise a [`CapacityError`] is returned and the string remains unchanged.
+    pub fn try_make_uppercase(&mut self) -> Result<(), CapacityError> {
+        if !self.convert_case_in_place(str::make_ascii_uppercase, char::to_uppercase) {
+            *self = self.try_map_chars(char::to_uppercase)?;
+        }
+        Ok(())
+    }
+
+    // decodes the character that starts at the byte position idx (data[idx..end] must be a part of the string that
+    // was not modified, and idx must be a char boundary before end)
+    #[inline(always)]
+    fn decode_char(&self, idx: usize, end: usize) -> char {
+        // safety: data[idx..end] is the end of a valid UTF-8 string (starting on a char boundary)
+        let text = unsafe { core::str::from_utf8_unchecked(&self.data[idx..end]) };
+        text.chars().next().unwrap()
+    }
+
+    // checks that idx is a char boundary inside the string (the end of the string is accepted only if allow_end is true)
+    fn check_index(&self, idx: usize, allow_end: bool) -> Result<(), IndexError> {
+        let len = self.len();
+        if idx > len || (idx == len && !allow_end) {
+            return Err(IndexError::OutOfRange { index: idx, len });
+        }
+        if !self.as_str().is_char_boundary(idx) {
+            return Err(IndexError::NotCharBoundary { index: idx });
+        }
+        Ok(())
     }
 }
 
-impl<const SIZE: usize> Deref for FlatString<SIZE> {
+impl<const SIZE: usize, L: Length> Deref for FlatString<SIZE, L> {
     type Target = str;
     fn deref(&self) -> &Self::Target {
         self.as_str()
     }
 }
 
-impl<const SIZE: usize> Default for FlatString<SIZE> {
+/// Gives access to the in-place methods of `str` (see [`FlatString::as_mut_str`]).
+///
+/// The cached number of characters is not updated through the `&mut str`: it remains correct for the safe methods of
+/// `str`, but unsafe code that changes the number of characters with `str::as_bytes_mut` breaks this logic invariant
+/// and makes `chars_count` (and the methods that rely on it) wrong until a new content is set.
+impl<const SIZE: usize, L: Length> DerefMut for FlatString<SIZE, L> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_str()
+    }
+}
+
+impl<const SIZE: usize, L: Length> AsRef<str> for FlatString<SIZE, L> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const SIZE: usize, L: Length> AsRef<[u8]> for FlatString<SIZE, L> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const SIZE: usize, L: Length> Borrow<str> for FlatString<SIZE, L> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const SIZE: usize, L: Length> Default for FlatString<SIZE, L> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<const SIZE: usize> std::fmt::Display for FlatString<SIZE> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl<const SIZE: usize, L: Length> core::fmt::Debug for FlatString<SIZE, L> {
+    /// Formats the content like a `str` (quoted and escaped). The alternate form (`{:#?}`) also shows the number of characters and the capacity.
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if f.alternate() {
+            f.debug_struct("FlatString")
+                .field("value", &self.as_str())
+                .field("chars", &self.chars_count())
+                .field("capacity", &SIZE)
+                .finish()
+        } else {
+            core::fmt::Debug::fmt(self.as_str(), f)
+        }
+    }
+}
+
+impl<const SIZE: usize, L: Length> core::fmt::Write for FlatString<SIZE, L> {
+    /// Appends a string slice to the FlatString. If the string slice does not fit in the available space, only the first characters that fit will be copied (just like `push_str` does) and `fmt::Error` will be returned (this stops the formatting of the remaining arguments).
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// use std::fmt::Write;
+    /// let mut s = FlatString::<8>::new();
+    /// assert!(write!(s, "{}", 123).is_ok());
+    /// assert!(write!(s, "-{}", 456789).is_err());
+    /// assert_eq!(s.as_str(), "123-4567");
+    /// ```
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if self.add_entire_string(s) {
+            Ok(())
+        } else {
+            self.fill_with_str(s);
+            Err(core::fmt::Error)
+        }
+    }
+}
+
+impl<const SIZE: usize, L: Length> core::fmt::Display for FlatString<SIZE, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         self.as_str().fmt(f)
     }
 }
+
+impl<const SIZE: usize, L: Length> PartialEq for FlatString<SIZE, L> {
+    #[cfg(not(feature = "zero-tail"))]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+
+    // the bytes after the end of the strings are always 0, so the whole buffers can be compared
+    #[cfg(feature = "zero-tail")]
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.data == other.data
+    }
+}
+
+impl<const SIZE: usize, L: Length> Eq for FlatString<SIZE, L> {}
+
+impl<const SIZE: usize, L: Length> PartialOrd for FlatString<SIZE, L> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const SIZE: usize, L: Length> Ord for FlatString<SIZE, L> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const SIZE: usize, L: Length> Hash for FlatString<SIZE, L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const SIZE: usize, L: Length> From<FlatString<SIZE, L>> for alloc::string::String {
+    fn from(value: FlatString<SIZE, L>) -> Self {
+        alloc::string::String::from(value.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const SIZE: usize, L: Length> PartialEq<alloc::string::String> for FlatString<SIZE, L> {
+    fn eq(&self, other: &alloc::string::String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const SIZE: usize, L: Length> PartialEq<FlatString<SIZE, L>> for alloc::string::String {
+    fn eq(&self, other: &FlatString<SIZE, L>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
@@ -0,0 +1,18 @@
+use crate::{FlatString, Length};
+use postcard::experimental::max_size::MaxSize;
+
+// the number of bytes of a varint encoded value (7 bits per byte)
+const fn varint_len(mut value: usize) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// A FlatString is serialized by postcard (through serde) as a string: the length as a varint followed by at most
+/// `SIZE` bytes.
+impl<const SIZE: usize, L: Length> MaxSize for FlatString<SIZE, L> {
+    const POSTCARD_MAX_SIZE: usize = varint_len(SIZE) + SIZE;
+}
@@ -0,0 +1,77 @@
+use crate::{FlatString, Length};
+use rkyv::rancor::Fallible;
+use rkyv::traits::{CopyOptimization, NoUndef};
+use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+// safety: FlatString is `repr(C)`, has an alignment of 1, has no padding and stores its lengths as little-endian byte
+// arrays, so it has the same representation on every platform and can be used as its own archived form
+unsafe impl<const SIZE: usize, L: Length> Portable for FlatString<SIZE, L> {}
+unsafe impl<const SIZE: usize, L: Length> NoUndef for FlatString<SIZE, L> {}
+
+/// The archived form of a `FlatString<SIZE, L>` is the FlatString itself (a fixed-size inline buffer), so an archived
+/// FlatString can be used directly (e.g. with `as_str`) without deserializing it.
+///
+/// The unused part of the buffer is written as zeros, so that old content (left in the buffer by `clear`, `truncate`,
+/// `pop`, ...) does not end up in the archive.
+impl<const SIZE: usize, L: Length> Archive for FlatString<SIZE, L> {
+    // safety: the archived form is the FlatString itself. The bytes are copied directly (e.g. for a slice of
+    // FlatStrings) only when the unused part of the buffer is always zero
+    const COPY_OPTIMIZATION: CopyOptimization<Self> =
+        unsafe { CopyOptimization::enable_if(cfg!(feature = "zero-tail")) };
+
+    type Archived = FlatString<SIZE, L>;
+    type Resolver = ();
+
+    #[inline]
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        let mut archived = *self;
+        archived.data[self.len()..].fill(0);
+        out.write(archived);
+    }
+}
+
+impl<S: Fallible + ?Sized, const SIZE: usize, L: Length> Serialize<S> for FlatString<SIZE, L> {
+    #[inline]
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized, const SIZE: usize, L: Length> Deserialize<FlatString<SIZE, L>, D> for FlatString<SIZE, L> {
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<FlatString<SIZE, L>, D::Error> {
+        Ok(*self)
+    }
+}
+
+mod check {
+    use crate::{layout, FlatString, FromBytesError, Length};
+    use core::fmt;
+    use rkyv::bytecheck::CheckBytes;
+    use rkyv::rancor::{Fallible, Source};
+
+    #[derive(Debug)]
+    struct InvalidFlatString(FromBytesError);
+    impl fmt::Display for InvalidFlatString {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "invalid FlatString: {}", self.0)
+        }
+    }
+    impl core::error::Error for InvalidFlatString {}
+
+    // safety: the invariants used by `as_str` are validated (the length fits in the capacity, the string is valid UTF-8
+    // and the number of characters matches)
+    unsafe impl<C, const SIZE: usize, L: Length> CheckBytes<C> for FlatString<SIZE, L>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        unsafe fn check_bytes(value: *const Self, _: &mut C) -> Result<(), C::Error> {
+            // safety: the caller guarantees that `value` is aligned and points to enough bytes, and any bit pattern is
+            // valid for the fields of a FlatString (they are all bytes)
+            let value = unsafe { &*value };
+            layout::validate::<SIZE, L>(&value.data, value.len, value.chars)
+                .map_err(|e| C::Error::new(InvalidFlatString(e)))
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{FlatString, Length};
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+impl<const SIZE: usize, L: Length> Serialize for FlatString<SIZE, L> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+// copies the deserialized string into the inline buffer (no allocation is needed, as the string
+// is borrowed from the input or from the deserializer's scratch buffer)
+struct FlatStringVisitor<const SIZE: usize, L: Length> {
+    truncate: bool,
+    _length: PhantomData<L>,
+}
+impl<const SIZE: usize, L: Length> FlatStringVisitor<SIZE, L> {
+    fn new(truncate: bool) -> Self {
+        Self {
+            truncate,
+            _length: PhantomData,
+        }
+    }
+}
+impl<const SIZE: usize, L: Length> Visitor<'_> for FlatStringVisitor<SIZE, L> {
+    type Value = FlatString<SIZE, L>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.truncate {
+            f.write_str("a string")
+        } else {
+            write!(f, "a string of at most {} bytes", SIZE)
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        if self.truncate {
+            Ok(FlatString::from_str(v))
+        } else {
+            FlatString::try_from_str(v).map_err(|_| E::invalid_length(v.len(), &self))
+        }
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match core::str::from_utf8(v) {
+            Ok(text) => self.visit_str(text),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+/// Deserializes a FlatString in strict mode: an error is returned if the string is larger than `SIZE` bytes.
+/// Use [`serde_truncate`] to truncate the string instead (like `from_str` does).
+impl<'de, const SIZE: usize, L: Length> Deserialize<'de> for FlatString<SIZE, L> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(FlatStringVisitor::new(false))
+    }
+}
+
+/// Serializes and deserializes a FlatString, truncating the deserialized string if it is larger than `SIZE` bytes
+/// (only the first characters that fit are kept, just like `from_str` does).
+///
+/// # Example
+/// ```rust
+/// use flat_string::FlatString;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     #[serde(with = "flat_string::serde_truncate")]
+///     name: FlatString<5>,
+///     id: FlatString<5>,
+/// }
+/// let c: Config = serde_json::from_str(r#"{"name": "Hello World", "id": "x1"}"#).unwrap();
+/// assert_eq!(c.name.as_str(), "Hello");
+/// assert!(serde_json::from_str::<Config>(r#"{"name": "a", "id": "too long"}"#).is_err());
+/// ```
+pub mod serde_truncate {
+    use super::FlatStringVisitor;
+    use crate::{FlatString, Length};
+    use serde::{Deserializer, Serialize, Serializer};
+
+    /// Serializes a FlatString as a string (the same as the `Serialize` implementation).
+    pub fn serialize<S: Serializer, const SIZE: usize, L: Length>(
+        value: &FlatString<SIZE, L>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize(serializer)
+    }
+
+    /// Deserializes a FlatString, truncating the string if it is larger than `SIZE` bytes.
+    pub fn deserialize<'de, D: Deserializer<'de>, const SIZE: usize, L: Length>(
+        deserializer: D,
+    ) -> Result<FlatString<SIZE, L>, D::Error> {
+        deserializer.deserialize_str(FlatStringVisitor::new(true))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const SIZE: usize, L: Length> Serialize for crate::FlatOrHeap<SIZE, L> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de, const SIZE: usize, L: Length> Deserialize<'de> for crate::FlatOrHeap<SIZE, L> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FlatOrHeapVisitor<const SIZE: usize, L: Length>(PhantomData<L>);
+        impl<const SIZE: usize, L: Length> Visitor<'_> for FlatOrHeapVisitor<SIZE, L> {
+            type Value = crate::FlatOrHeap<SIZE, L>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(crate::FlatOrHeap::from_str(v))
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                match core::str::from_utf8(v) {
+                    Ok(text) => self.visit_str(text),
+                    Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(v), &self)),
+                }
+            }
+        }
+        deserializer.deserialize_str(FlatOrHeapVisitor(PhantomData))
+    }
+}
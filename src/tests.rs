@@ -1,4 +1,6 @@
-use crate::FlatString;
+use crate::{CapacityError, FlatString, IndexError, InsertError, Truncation};
+use std::format;
+use std::prelude::rust_2021::*;
 
 #[test]
 fn check_create_empty() {
@@ -201,6 +203,54 @@ fn check_display() {
     assert_eq!(format!("{}", s), "Hello");
 }
 
+#[test]
+fn check_debug() {
+    let mut s = FlatString::<10>::from_str("Hello\"\n!");
+    assert_eq!(format!("{:?}", s), "\"Hello\\\"\\n!\"");
+    s.truncate(5);
+    assert_eq!(format!("{:?}", s), "\"Hello\"");
+    assert_eq!(format!("{:?}", Some(s)), "Some(\"Hello\")");
+}
+
+#[test]
+fn check_debug_alternate() {
+    let s = FlatString::<10>::from_str("añb");
+    assert_eq!(
+        format!("{:#?}", s),
+        "FlatString {\n    value: \"añb\",\n    chars: 3,\n    capacity: 10,\n}"
+    );
+}
+
+#[test]
+fn check_fmt_write() {
+    use std::fmt::Write;
+
+    let mut s = FlatString::<10>::new();
+    assert!(write!(s, "{}+{}", 1, 2).is_ok());
+    assert_eq!(s.as_str(), "1+2");
+    assert!(s.write_char('=').is_ok());
+    assert!(write!(s, "{:.3}", 3.0f64).is_ok());
+    assert_eq!(s.as_str(), "1+2=3.000");
+    assert!(write!(s, "ん").is_err());
+    assert_eq!(s.as_str(), "1+2=3.000");
+    assert_eq!(s.chars_count(), 9);
+}
+
+#[test]
+fn check_flat_format() {
+    let s = crate::flat_format!(20; "{}:{:>3}", "key", 7);
+    assert_eq!(s.as_str(), "key:  7");
+    assert_eq!(s.capacity(), 20);
+
+    let s: FlatString<6> = crate::flat_format!("{}", "こんにちは");
+    assert_eq!(s.as_str(), "こん");
+    assert_eq!(s.chars_count(), 2);
+
+    let name = "abc";
+    let s: FlatString = crate::flat_format!("{name}{name}");
+    assert_eq!(s.as_str(), "abcabc");
+}
+
 #[test]
 fn check_truncate() {
     let mut s = FlatString::<10>::from_str("Hello");
@@ -410,3 +460,1146 @@ fn check_remove_panic_middle() {
     let mut s = FlatString::<20>::from_str("aんbcd");
     s.remove(2);
 }
+
+#[test]
+fn check_eq_ignores_stale_bytes() {
+    let mut a = FlatString::<10>::from_str("abcdef");
+    a.truncate(3);
+    let b = FlatString::<10>::from_str("abc");
+    assert_eq!(a, b);
+
+    let mut a = FlatString::<10>::from_str("abcx");
+    assert_eq!(a.pop(), Some('x'));
+    assert_eq!(a, b);
+
+    let mut a = FlatString::<10>::from_str("Hello");
+    let mut b = FlatString::<10>::from_str("World");
+    a.clear();
+    b.clear();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn check_ord_matches_str() {
+    let mut a = FlatString::<10>::from_str("abz");
+    a.truncate(2);
+    let b = FlatString::<10>::from_str("abc");
+    assert!(a < b);
+    assert_eq!(a.cmp(&b), "ab".cmp("abc"));
+
+    let mut words = ["pear", "apple", "fig", "apricot"].map(FlatString::<10>::from_str);
+    words.sort();
+    assert_eq!(words.map(|w| w.as_str().to_string()), ["apple", "apricot", "fig", "pear"]);
+}
+
+#[test]
+fn check_hash_ignores_stale_bytes() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut h = DefaultHasher::new();
+        value.hash(&mut h);
+        h.finish()
+    }
+    let mut a = FlatString::<10>::from_str("Hello!");
+    a.remove(5);
+    let b = FlatString::<10>::from_str("Hello");
+    assert_eq!(hash_of(&a), hash_of(&b));
+    assert_eq!(hash_of(&a), hash_of("Hello"));
+}
+
+#[test]
+fn check_btreemap_key() {
+    use std::collections::BTreeMap;
+
+    let mut map = BTreeMap::new();
+    let mut key = FlatString::<10>::from_str("key1");
+    map.insert(key, 1);
+    key.truncate(3);
+    key.push('1');
+    assert_eq!(map.get(&key), Some(&1));
+}
+
+#[test]
+fn check_hashmap_lookup_by_str() {
+    use std::collections::{HashMap, HashSet};
+
+    let mut map: HashMap<FlatString<32>, u32> = HashMap::new();
+    map.insert(FlatString::from_str("alpha"), 1);
+    map.insert(FlatString::from_str("beta"), 2);
+    assert_eq!(map.get("alpha"), Some(&1));
+    assert_eq!(map.get("beta"), Some(&2));
+    assert_eq!(map.get("gamma"), None);
+
+    let mut set: HashSet<FlatString<8>> = HashSet::new();
+    set.insert(FlatString::from_str("x"));
+    assert!(set.contains("x"));
+    assert!(!set.contains("y"));
+}
+
+#[test]
+fn check_as_ref() {
+    fn str_len<T: AsRef<str>>(value: T) -> usize {
+        value.as_ref().len()
+    }
+    fn first_byte<T: AsRef<[u8]>>(value: T) -> Option<u8> {
+        value.as_ref().first().copied()
+    }
+    let s = FlatString::<10>::from_str("Hello");
+    assert_eq!(str_len(s), 5);
+    assert_eq!(first_byte(s), Some(b'H'));
+    assert_eq!(AsRef::<[u8]>::as_ref(&FlatString::<4>::new()), b"");
+}
+
+#[test]
+fn check_try_from_str() {
+    let s = FlatString::<5>::try_from_str("Hello").unwrap();
+    assert_eq!(s.as_str(), "Hello");
+    assert_eq!(s.chars_count(), 5);
+    assert_eq!(FlatString::<5>::try_from_str("こんに"), Err(CapacityError::new(9, 5)));
+}
+
+#[test]
+fn check_try_set() {
+    let mut s = FlatString::<6>::from_str("abc");
+    assert_eq!(s.try_set("こんに"), Err(CapacityError::new(9, 6)));
+    assert_eq!(s.as_str(), "abc");
+    assert_eq!(s.try_set("こん"), Ok(()));
+    assert_eq!(s.as_str(), "こん");
+    assert_eq!(s.chars_count(), 2);
+    assert_eq!(s.len(), 6);
+}
+
+#[test]
+fn check_try_insert() {
+    let mut s = FlatString::<10>::from_str("aんb");
+    assert_eq!(s.try_insert(4, "xy"), Ok(()));
+    assert_eq!(s.as_str(), "aんxyb");
+    assert_eq!(s.chars_count(), 5);
+    assert_eq!(s.len(), 7);
+    assert_eq!(s.try_insert(7, "!"), Ok(()));
+    assert_eq!(s.as_str(), "aんxyb!");
+    assert_eq!(
+        s.try_insert(0, "こん"),
+        Err(InsertError::Capacity(CapacityError::new(6, 2)))
+    );
+    assert_eq!(
+        s.try_insert(2, "z"),
+        Err(InsertError::Index(IndexError::NotCharBoundary { index: 2 }))
+    );
+    assert_eq!(
+        s.try_insert(9, "z"),
+        Err(InsertError::Index(IndexError::OutOfRange { index: 9, len: 8 }))
+    );
+    assert_eq!(s.as_str(), "aんxyb!");
+    assert_eq!(s.try_insert_char(0, 'ñ'), Ok(()));
+    assert_eq!(s.as_str(), "ñaんxyb!");
+    assert_eq!(s.chars_count(), 7);
+    assert!(s.try_insert_char(0, 'x').is_err());
+}
+
+#[test]
+fn check_try_remove() {
+    let mut s = FlatString::<10>::from_str("aんb");
+    assert_eq!(s.try_remove(3), Err(IndexError::NotCharBoundary { index: 3 }));
+    assert_eq!(s.try_remove(5), Err(IndexError::OutOfRange { index: 5, len: 5 }));
+    assert_eq!(s.as_str(), "aんb");
+    assert_eq!(s.try_remove(4), Ok('b'));
+    assert_eq!(s.try_remove(0), Ok('a'));
+    assert_eq!(s.as_str(), "ん");
+    assert_eq!(s.chars_count(), 1);
+}
+
+#[test]
+fn check_error_display() {
+    assert_eq!(
+        CapacityError::new(9, 5).to_string(),
+        "not enough space in FlatString: 9 bytes needed, but only 5 bytes available"
+    );
+    assert_eq!(
+        IndexError::OutOfRange { index: 7, len: 3 }.to_string(),
+        "byte index 7 is out of range (the string has 3 bytes)"
+    );
+    assert_eq!(
+        InsertError::Index(IndexError::NotCharBoundary { index: 1 }).to_string(),
+        "byte index 1 is not a char boundary"
+    );
+}
+
+#[test]
+fn check_push_str_with_report() {
+    let mut s = FlatString::<10>::new();
+    let r = s.push_str_with_report("Hello");
+    assert_eq!(r, Truncation { written: 5, dropped: 0 });
+    assert!(!r.is_truncated());
+    let r = s.push_str_with_report("こんに");
+    assert_eq!(s.as_str(), "Helloこ");
+    assert_eq!(r, Truncation { written: 3, dropped: 6 });
+    assert!(r.is_truncated());
+    assert_eq!(r.remainder("こんに"), "んに");
+    let r = s.push_str_with_report("ん");
+    assert_eq!(r, Truncation { written: 0, dropped: 3 });
+    assert_eq!(s.as_str(), "Helloこ");
+}
+
+#[test]
+fn check_set_with_report() {
+    let mut s = FlatString::<4>::from_str("abcd");
+    let r = s.set_with_report("zăpadă");
+    assert_eq!(s.as_str(), "zăp");
+    assert_eq!(s.chars_count(), 3);
+    assert_eq!(r, Truncation { written: 4, dropped: 4 });
+    let r = s.set_with_report("xy");
+    assert_eq!(s.as_str(), "xy");
+    assert!(!r.is_truncated());
+}
+
+#[test]
+fn check_from_str_with_report() {
+    let (s, r) = FlatString::<10>::from_str_with_report("こんにちは世界");
+    assert_eq!(s.as_str(), "こんに");
+    assert_eq!(r, Truncation { written: 9, dropped: 12 });
+}
+
+#[test]
+fn check_insert_with_report() {
+    let mut s = FlatString::<10>::from_str("abcde");
+    let r = s.insert_with_report(2, "0123456789");
+    assert_eq!(s.as_str(), "ab01234567");
+    assert_eq!(r, Truncation { written: 8, dropped: 2 });
+
+    let mut s = FlatString::<10>::from_str("abcde");
+    let r = s.insert_with_report(3, "012");
+    assert_eq!(s.as_str(), "abc012de");
+    assert!(!r.is_truncated());
+}
+
+#[test]
+fn check_insert_with_report_splitting_a_char() {
+    // the last 'ă' can not be shifted, so it is dropped
+    let mut s = FlatString::<12>::from_str("ăăăăăă");
+    let r = s.insert_with_report(10, " ");
+    assert_eq!(s.as_str(), "ăăăăă ");
+    assert_eq!(s.chars_count(), 6);
+    assert!(!r.is_truncated());
+
+    // the written part ends inside the second '日', so it is dropped
+    let mut s = FlatString::<6>::from_str("日日");
+    let r = s.insert_with_report(0, "X日日");
+    assert_eq!(s.as_str(), "X日");
+    assert_eq!(s.chars_count(), 2);
+    assert_eq!(r, Truncation { written: 4, dropped: 3 });
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_string_conversions() {
+    let s = FlatString::<10>::from_str("Hello");
+    let owned: String = s.into();
+    assert_eq!(owned, "Hello");
+    assert_eq!(s, owned);
+    assert_eq!(owned, s);
+    assert_ne!(s, String::from("Hell"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn check_std_error() {
+    fn as_error<E: std::error::Error + 'static>(e: E) -> Box<dyn std::error::Error> {
+        Box::new(e)
+    }
+    let e = as_error(FlatString::<2>::try_from_str("abc").unwrap_err());
+    assert_eq!(e.to_string(), "not enough space in FlatString: 3 bytes needed, but only 2 bytes available");
+}
+
+#[test]
+fn check_const_construction() {
+    const EMPTY: FlatString<4> = FlatString::new();
+    const NAME: FlatString<16> = FlatString::from_str("default");
+    const CUT: FlatString<10> = FlatString::from_str("こんにちは世界");
+    const TABLE: [FlatString<6>; 3] = [
+        FlatString::from_str("red"),
+        FlatString::from_str("green"),
+        FlatString::from_str("blue"),
+    ];
+    const NAME_LEN: usize = NAME.len();
+    const NAME_STR: &str = NAME.as_str();
+
+    assert!(EMPTY.is_empty());
+    assert_eq!(EMPTY.capacity(), 4);
+    assert_eq!(NAME_LEN, 7);
+    assert_eq!(NAME_STR, "default");
+    assert_eq!(NAME.chars_count(), 7);
+    assert_eq!(CUT.as_str(), "こんに");
+    assert_eq!(CUT.len(), 9);
+    assert_eq!(CUT.chars_count(), 3);
+    assert_eq!(TABLE[1].as_str(), "green");
+    assert_eq!(TABLE, [FlatString::<6>::from_str("red"), FlatString::from_str("green"), FlatString::from_str("blue")]);
+}
+
+#[test]
+fn check_from_str_matches_push_str() {
+    for text in ["", "a", "zăpadă la școală", "こんにちは世界", "🦀🦀🦀", "aé😀b"] {
+        let mut pushed = FlatString::<7>::new();
+        pushed.push_str(text);
+        let created = FlatString::<7>::from_str(text);
+        assert_eq!(created.as_str(), pushed.as_str());
+        assert_eq!(created.len(), pushed.len());
+        assert_eq!(created.chars_count(), pushed.chars_count());
+    }
+}
+
+#[test]
+fn check_flat_str() {
+    const GREETING: FlatString<5> = crate::flat_str!("Hello");
+    assert_eq!(GREETING.as_str(), "Hello");
+    let s = crate::flat_str!("zăpadă");
+    assert_eq!(s.capacity(), 8);
+    assert_eq!(s.chars_count(), 6);
+    assert_eq!(s.as_str(), "zăpadă");
+    let s = crate::flat_str!("");
+    assert_eq!(s.capacity(), 1);
+    assert!(s.is_empty());
+    let s = crate::flat_str!(concat!("ab", "cd"));
+    assert_eq!(std::mem::size_of_val(&s), 6);
+}
+
+#[test]
+fn check_u16_length() {
+    let text = "0123456789".repeat(40);
+    let mut s = FlatString::<300, u16>::from_str(&text);
+    assert_eq!(s.len(), 300);
+    assert_eq!(s.chars_count(), 300);
+    assert_eq!(s.capacity(), 300);
+    assert_eq!(s.as_str(), &text[..300]);
+    s.truncate(280);
+    s.push_str("こんにちは世界");
+    assert_eq!(s.len(), 298);
+    assert_eq!(s.chars_count(), 286);
+    assert!(s.ends_with("こんにちは世"));
+    assert_eq!(s.pop(), Some('世'));
+    s.insert(0, "ab");
+    assert_eq!(s.len(), 297);
+    assert!(s.starts_with("ab0123"));
+    assert_eq!(s.remove(1), 'b');
+    assert_eq!(s.try_insert_char(1, 'ñ'), Ok(()));
+    assert!(s.starts_with("añ0123"));
+    assert_eq!(s.chars_count(), 287);
+    s.clear();
+    assert!(s.is_empty());
+}
+
+#[test]
+fn check_u32_length() {
+    let text = "ș".repeat(40_000);
+    let s = FlatString::<70_000, u32>::from_str(&text);
+    assert_eq!(s.len(), 70_000);
+    assert_eq!(s.chars_count(), 35_000);
+    let s = FlatString::<5, u32>::try_from_str("Hello").unwrap();
+    assert_eq!(s, FlatString::<5, u32>::from_str("Hello"));
+    assert_eq!(format!("{:?}", s), "\"Hello\"");
+}
+
+#[test]
+fn check_length_type_memory_size() {
+    assert_eq!(std::mem::size_of::<FlatString<14, u8>>(), 16);
+    assert_eq!(std::mem::size_of::<FlatString<300, u16>>(), 304);
+    assert_eq!(std::mem::size_of::<FlatString<301, u16>>(), 305);
+    assert_eq!(std::mem::size_of::<FlatString<301, u32>>(), 309);
+    assert_eq!(std::mem::align_of::<FlatString<301, u32>>(), 1);
+}
+
+#[test]
+fn check_length_type_const() {
+    const LONG: FlatString<1000, u16> = FlatString::from_str("const with a u16 length");
+    const LONG_LEN: usize = LONG.len();
+    assert_eq!(LONG_LEN, 23);
+    assert_eq!(LONG.as_str(), "const with a u16 length");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_flat_or_heap_spill() {
+    use crate::FlatOrHeap;
+
+    let mut s = FlatOrHeap::<8>::new();
+    assert!(s.is_inline());
+    s.push_str("Hello");
+    s.push(' ');
+    assert!(s.is_inline());
+    assert_eq!(s.as_flat().map(|f| f.as_str()), Some("Hello "));
+    s.push_str("World !");
+    assert!(s.is_heap());
+    assert_eq!(s.as_str(), "Hello World !");
+    assert_eq!(s.len(), 13);
+    assert_eq!(s.chars_count(), 13);
+    assert!(s.capacity() >= 13);
+    assert_eq!(s.as_flat(), None);
+
+    s.truncate(5);
+    assert!(s.is_heap());
+    assert!(s.shrink_to_inline());
+    assert_eq!(s.as_str(), "Hello");
+    assert_eq!(s.capacity(), 8);
+
+    s.clear();
+    assert!(s.is_inline());
+    assert!(s.is_empty());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_flat_or_heap_methods() {
+    use crate::FlatOrHeap;
+    use std::fmt::Write;
+
+    let mut s = FlatOrHeap::<6>::from_str("aんb");
+    assert!(s.is_inline());
+    s.insert(1, "xy");
+    assert!(s.is_heap());
+    assert_eq!(s.as_str(), "axyんb");
+    s.insert_char(0, '>');
+    assert_eq!(s.remove(4), 'ん');
+    assert_eq!(s.pop(), Some('b'));
+    assert_eq!(s.as_str(), ">axy");
+    assert!(s.shrink_to_inline());
+    assert_eq!(s.remove(0), '>');
+    assert_eq!(s.pop(), Some('y'));
+    assert_eq!(s.as_str(), "ax");
+
+    s.set("こんにちは");
+    assert!(s.is_heap());
+    assert_eq!(s.chars_count(), 5);
+    assert!(s.capacity() >= 15);
+    s.set("日本語!");
+    assert!(s.is_heap());
+    assert_eq!(s.as_str(), "日本語!");
+    s.set("ok");
+    assert!(s.is_inline());
+
+    write!(s, "-{}-{}", 12345, 67890).unwrap();
+    assert_eq!(s.as_str(), "ok-12345-67890");
+    assert_eq!(format!("{:?}", s), "\"ok-12345-67890\"");
+    assert_eq!(s, FlatOrHeap::<6>::from_str("ok-12345-67890"));
+    assert!(s.starts_with("ok"));
+    let owned: String = s.into();
+    assert_eq!(owned, "ok-12345-67890");
+
+    let s: FlatOrHeap<300, u16> = FlatString::<300, u16>::from_str("inline").into();
+    assert!(s.is_inline());
+    assert_eq!(s.into_string(), "inline");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_flat_or_heap_try_methods() {
+    use crate::FlatOrHeap;
+
+    let mut s = FlatOrHeap::<5>::from_str("aんb");
+    assert_eq!(s.try_insert(2, "x"), Err(IndexError::NotCharBoundary { index: 2 }));
+    assert_eq!(s.try_insert(6, "x"), Err(IndexError::OutOfRange { index: 6, len: 5 }));
+    assert!(s.is_inline());
+    assert_eq!(s.try_insert(5, "cd"), Ok(()));
+    assert!(s.is_heap());
+    assert_eq!(s.try_insert_char(0, '>'), Ok(()));
+    assert_eq!(s.as_str(), ">aんbcd");
+    assert_eq!(s.try_remove(3), Err(IndexError::NotCharBoundary { index: 3 }));
+    assert_eq!(s.try_remove(8), Err(IndexError::OutOfRange { index: 8, len: 8 }));
+    assert_eq!(s.try_remove(2), Ok('ん'));
+    assert_eq!(s.as_str(), ">abcd");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[should_panic]
+fn check_flat_or_heap_insert_panic() {
+    let mut s = crate::FlatOrHeap::<6>::from_str("aんb");
+    s.insert(2, "x");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn check_serde_roundtrip() {
+    let s = FlatString::<10>::from_str("Hi \"ん\"");
+    let json = serde_json::to_string(&s).unwrap();
+    assert_eq!(json, r#""Hi \"ん\"""#);
+    let back: FlatString<10> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, s);
+    let back: FlatString<300, u16> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.as_str(), s.as_str());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn check_serde_strict() {
+    let e = serde_json::from_str::<FlatString<4>>(r#""Hello""#).unwrap_err();
+    assert!(e.to_string().contains("a string of at most 4 bytes"));
+    assert!(serde_json::from_str::<FlatString<4>>("12").is_err());
+    let s: FlatString<5> = serde_json::from_str(r#""Hello""#).unwrap();
+    assert_eq!(s.as_str(), "Hello");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn check_serde_truncate() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Record {
+        #[serde(with = "crate::serde_truncate")]
+        name: FlatString<6>,
+    }
+    let r: Record = serde_json::from_str(r#"{"name": "こんにちは"}"#).unwrap();
+    assert_eq!(r.name.as_str(), "こん");
+    assert_eq!(serde_json::to_string(&r).unwrap(), r#"{"name":"こん"}"#);
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[test]
+fn check_serde_flat_or_heap() {
+    let s: crate::FlatOrHeap<4> = serde_json::from_str(r#""Hello World""#).unwrap();
+    assert!(s.is_heap());
+    assert_eq!(serde_json::to_string(&s).unwrap(), r#""Hello World""#);
+}
+
+#[cfg(feature = "zero-tail")]
+#[test]
+fn check_zero_tail() {
+    fn tail_is_zero<const SIZE: usize>(s: &FlatString<SIZE>) -> bool {
+        s.data[s.len()..].iter().all(|b| *b == 0)
+    }
+    let mut s = FlatString::<10>::from_str("Hello World");
+    s.truncate(7);
+    assert!(tail_is_zero(&s));
+    assert_eq!(s.pop(), Some('W'));
+    assert!(tail_is_zero(&s));
+    assert_eq!(s.remove(0), 'H');
+    assert_eq!(s.data, *b"ello \0\0\0\0\0");
+    s.set("ab");
+    assert!(tail_is_zero(&s));
+    s.set("ABCHんこ");
+    s.insert(0, "01");
+    assert_eq!(s.as_str(), "01ABCHん");
+    assert!(tail_is_zero(&s));
+    s.clear();
+    assert_eq!(s.data, [0; 10]);
+
+    // the buffers are identical, so the strings can be compared as raw bytes
+    let mut a = FlatString::<10>::from_str("abcdef");
+    a.truncate(3);
+    let b = FlatString::<10>::from_str("abc");
+    assert_eq!(a.data, b.data);
+    assert_eq!(a, b);
+    assert_ne!(FlatString::<10>::from_str("ab\0"), FlatString::<10>::from_str("ab"));
+}
+
+#[test]
+fn check_raw_bytes_layout() {
+    let s = FlatString::<4>::from_str("añ");
+    assert_eq!(s.as_raw_bytes(), &[b'a', 0xC3, 0xB1, 0, 3, 2]);
+    let s = FlatString::<3, u16>::from_str("abc");
+    assert_eq!(s.as_raw_bytes(), b"abc\x03\x00\x03\x00");
+    let s = FlatString::<3, u32>::from_str("ab");
+    assert_eq!(s.as_raw_bytes(), b"ab\x00\x02\x00\x00\x00\x02\x00\x00\x00");
+}
+
+#[test]
+fn check_ref_from_bytes() {
+    use crate::FromBytesError;
+
+    let s = FlatString::<6, u16>::from_str("こん");
+    let bytes = s.as_raw_bytes().to_vec();
+    let r = FlatString::<6, u16>::ref_from_bytes(&bytes).unwrap();
+    assert_eq!(r.as_str(), "こん");
+    assert_eq!(r.chars_count(), 2);
+    assert_eq!(FlatString::<6, u16>::read_from_bytes(&bytes), Ok(s));
+
+    assert_eq!(
+        FlatString::<6, u16>::ref_from_bytes(&bytes[1..]),
+        Err(FromBytesError::Size { expected: 10, actual: 9 })
+    );
+    let mut bad = bytes.clone();
+    bad[6] = 7;
+    assert_eq!(
+        FlatString::<6, u16>::ref_from_bytes(&bad),
+        Err(FromBytesError::Length { len: 7, capacity: 6 })
+    );
+    let mut bad = bytes.clone();
+    bad[6] = 4;
+    assert_eq!(FlatString::<6, u16>::ref_from_bytes(&bad), Err(FromBytesError::Utf8));
+    let mut bad = bytes.clone();
+    bad[8] = 3;
+    assert_eq!(
+        FlatString::<6, u16>::ref_from_bytes(&bad),
+        Err(FromBytesError::CharsCount { stored: 3, actual: 2 })
+    );
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn check_bytemuck() {
+    let s = FlatString::<8>::from_str("Hello");
+    let bytes: &[u8] = bytemuck::bytes_of(&s);
+    assert_eq!(bytes.len(), 10);
+    let back: &FlatString<8> = bytemuck::checked::try_from_bytes(bytes).unwrap();
+    assert_eq!(back.as_str(), "Hello");
+    let mut bad = bytes.to_vec();
+    bad[9] = 4;
+    assert!(bytemuck::checked::try_from_bytes::<FlatString<8>>(&bad).is_err());
+    let zeroed: FlatString<8> = bytemuck::Zeroable::zeroed();
+    assert!(zeroed.is_empty());
+}
+
+#[cfg(feature = "zerocopy")]
+#[test]
+fn check_zerocopy() {
+    #[derive(zerocopy::IntoBytes, zerocopy::Immutable)]
+    #[repr(C)]
+    struct Message {
+        id: [u8; 2],
+        name: FlatString<4>,
+    }
+    let m = Message {
+        id: [1, 2],
+        name: FlatString::from_str("abc"),
+    };
+    let bytes = zerocopy::IntoBytes::as_bytes(&m);
+    assert_eq!(bytes, &[1, 2, b'a', b'b', b'c', 0, 3, 3]);
+    let name = FlatString::<4>::ref_from_bytes(&bytes[2..]).unwrap();
+    assert_eq!(name.as_str(), "abc");
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn check_rkyv_archive() {
+    #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize, Debug, PartialEq)]
+    struct Snapshot {
+        id: u32,
+        name: FlatString<15>,
+        path: FlatString<300, u16>,
+    }
+    let snapshot = Snapshot {
+        id: 7,
+        name: FlatString::from_str("こんにちは"),
+        path: FlatString::from_str("/var/lib/data"),
+    };
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&snapshot).unwrap();
+    let archived = rkyv::access::<ArchivedSnapshot, rkyv::rancor::Error>(&bytes).unwrap();
+    assert_eq!(archived.name.as_str(), "こんにちは");
+    assert_eq!(archived.name.chars_count(), 5);
+    assert_eq!(archived.path.as_str(), "/var/lib/data");
+    let back = rkyv::deserialize::<Snapshot, rkyv::rancor::Error>(archived).unwrap();
+    assert_eq!(back, snapshot);
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn check_rkyv_validation() {
+    let s = FlatString::<8>::from_str("Hello");
+    let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&s).unwrap();
+    assert_eq!(&bytes[..], s.as_raw_bytes());
+    let archived = rkyv::access::<FlatString<8>, rkyv::rancor::Error>(&bytes).unwrap();
+    assert_eq!(archived.as_str(), "Hello");
+    bytes[8] = 9;
+    assert!(rkyv::access::<FlatString<8>, rkyv::rancor::Error>(&bytes).is_err());
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn check_rkyv_archive_does_not_contain_old_content() {
+    let mut s = FlatString::<8>::from_str("password");
+    s.truncate(2);
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&s).unwrap();
+    assert_eq!(&bytes[..8], b"pa\0\0\0\0\0\0");
+    // a slice of FlatStrings is written without visiting every element only if the tail is always zero
+    let mut t = FlatString::<8>::from_str("secret");
+    t.clear();
+    let v = std::vec![s, t];
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&v).unwrap();
+    assert!(!bytes.windows(6).any(|w| w == b"secret"));
+    assert!(!bytes.windows(6).any(|w| w == b"ssword"));
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn check_bincode() {
+    let config = bincode::config::standard();
+    let s = FlatString::<20>::from_str("zăpadă");
+    let bytes = bincode::encode_to_vec(s, config).unwrap();
+    assert_eq!(bytes, bincode::encode_to_vec("zăpadă", config).unwrap());
+    assert_eq!(bytes.len(), 1 + s.len());
+    let (d, read) = bincode::decode_from_slice::<FlatString<20>, _>(&bytes, config).unwrap();
+    assert_eq!(read, bytes.len());
+    assert_eq!(d.as_str(), "zăpadă");
+    assert_eq!(d.chars_count(), 6);
+    let (d, _) = bincode::borrow_decode_from_slice::<FlatString<8>, _>(&bytes, config).unwrap();
+    assert_eq!(d.as_str(), "zăpadă");
+    // too large for the capacity
+    assert!(bincode::decode_from_slice::<FlatString<7>, _>(&bytes, config).is_err());
+    // invalid UTF-8
+    assert!(bincode::decode_from_slice::<FlatString<8>, _>(&[2, 0xC3, 0x28], config).is_err());
+}
+
+#[cfg(feature = "postcard")]
+#[test]
+fn check_postcard() {
+    use postcard::experimental::max_size::MaxSize;
+    assert_eq!(FlatString::<20>::POSTCARD_MAX_SIZE, 21);
+    assert_eq!(FlatString::<200>::POSTCARD_MAX_SIZE, 202);
+    let s = FlatString::<20>::from_str("Hello");
+    let mut buffer = [0u8; FlatString::<20>::POSTCARD_MAX_SIZE];
+    let used = postcard::to_slice(&s, &mut buffer).unwrap();
+    assert_eq!(used, &[5, b'H', b'e', b'l', b'l', b'o']);
+    let d: FlatString<20> = postcard::from_bytes(used).unwrap();
+    assert_eq!(d.as_str(), "Hello");
+    assert!(postcard::from_bytes::<FlatString<4>>(used).is_err());
+}
+
+#[cfg(feature = "borsh")]
+#[test]
+fn check_borsh() {
+    let s = FlatString::<20>::from_str("zăpadă");
+    let bytes = borsh::to_vec(&s).unwrap();
+    assert_eq!(bytes, borsh::to_vec("zăpadă").unwrap());
+    assert_eq!(bytes.len(), 4 + s.len());
+    let d: FlatString<20> = borsh::from_slice(&bytes).unwrap();
+    assert_eq!(d.as_str(), "zăpadă");
+    assert_eq!(d.chars_count(), 6);
+    assert!(borsh::from_slice::<FlatString<7>>(&bytes).is_err());
+    assert!(borsh::from_slice::<FlatString<8>>(&[2, 0, 0, 0, 0xC3, 0x28]).is_err());
+}
+
+#[cfg(feature = "unicode-segmentation")]
+#[test]
+fn check_graphemes() {
+    // "e" + combining acute accent, and a family emoji (4 people joined by ZWJ)
+    let family = "👨\u{200d}👩\u{200d}👧\u{200d}👦";
+    let mut s = FlatString::<32>::from_str("e\u{301}");
+    s.push_str(family);
+    assert_eq!(s.graphemes_count(), 2);
+    assert_eq!(s.chars_count(), 9);
+
+    let mut s = FlatString::<30>::from_str("ab");
+    s.push_str_graphemes(family);
+    s.push_str_graphemes("e\u{301}e\u{301}");
+    assert_eq!(s.as_str(), "ab👨\u{200d}👩\u{200d}👧\u{200d}👦e\u{301}");
+    assert_eq!(s.chars_count(), 11);
+
+    assert_eq!(s.pop_grapheme().unwrap().as_str(), "e\u{301}");
+    assert_eq!(s.pop_grapheme().unwrap().as_str(), family);
+    assert_eq!(s.as_str(), "ab");
+    assert_eq!(s.chars_count(), 2);
+    assert_eq!(s.pop_grapheme().unwrap().as_str(), "b");
+    assert_eq!(s.pop_grapheme().unwrap().as_str(), "a");
+    assert!(s.pop_grapheme().is_none());
+
+    let mut s = FlatString::<40>::from_str("x");
+    s.push_str(family);
+    s.push_str("y");
+    s.truncate_graphemes(5);
+    assert_eq!(s.graphemes_count(), 3);
+    s.truncate_graphemes(2);
+    assert_eq!(s.as_str(), "x👨\u{200d}👩\u{200d}👧\u{200d}👦");
+    assert_eq!(s.chars_count(), 8);
+    s.truncate_graphemes(0);
+    assert!(s.is_empty());
+}
+
+#[cfg(feature = "unicode-width")]
+#[test]
+fn check_display_width() {
+    let mut s = FlatString::<32>::from_str("id: 日本語 ok");
+    assert_eq!(s.display_width(), 13);
+    s.truncate_to_width(20);
+    assert_eq!(s.as_str(), "id: 日本語 ok");
+    s.truncate_to_width(8);
+    assert_eq!(s.as_str(), "id: 日本");
+    assert_eq!(s.chars_count(), 6);
+    s.truncate_to_width(0);
+    assert!(s.is_empty());
+
+    // combining marks do not take any column
+    let s = FlatString::<10>::from_str("e\u{301}");
+    assert_eq!(s.display_width(), 1);
+
+    // limited by the width
+    let mut s = FlatString::<32>::new();
+    s.push_str_fitting_width("ab", 5);
+    s.push_str_fitting_width("日本語", 5);
+    assert_eq!(s.as_str(), "ab日");
+    s.push_str_fitting_width("x", 5);
+    assert_eq!(s.as_str(), "ab日x");
+    s.push_str_fitting_width("y", 5);
+    assert_eq!(s.as_str(), "ab日x");
+    // limited by the capacity
+    let mut s = FlatString::<7>::from_str("a");
+    s.push_str_fitting_width("日本語", 20);
+    assert_eq!(s.as_str(), "a日本");
+    assert_eq!(s.chars_count(), 3);
+}
+
+#[test]
+fn check_ellipsis() {
+    let mut s = FlatString::<10>::new();
+    let r = s.set_with_ellipsis("Hello", "…");
+    assert_eq!(s.as_str(), "Hello");
+    assert!(!r.is_truncated());
+
+    // the text is cut at a char boundary to make room for the marker
+    let r = s.set_with_ellipsis("zăpadă mare", "…");
+    assert_eq!(s.as_str(), "zăpad…");
+    assert_eq!(s.len(), 9);
+    assert_eq!(s.chars_count(), 6);
+    assert_eq!(r.written, 6);
+    assert_eq!(r.remainder("zăpadă mare"), "ă mare");
+
+    let mut s = FlatString::<10>::from_str("ab");
+    s.push_str_with_ellipsis("cdefghijkl", "...");
+    assert_eq!(s.as_str(), "abcdefg...");
+    assert_eq!(s.chars_count(), 10);
+
+    // no room for the whole marker
+    let mut s = FlatString::<10>::from_str("12345678");
+    let r = s.push_str_with_ellipsis("abc", "...");
+    assert_eq!(s.as_str(), "12345678..");
+    assert_eq!(r.written, 0);
+    let r = s.push_str_with_ellipsis("", "...");
+    assert!(!r.is_truncated());
+    assert_eq!(s.as_str(), "12345678..");
+}
+
+#[test]
+fn check_padding() {
+    let mut s = FlatString::<10>::from_str("ăb");
+    s.pad_left(4, ' ');
+    assert_eq!(s.as_str(), "  ăb");
+    s.pad_right(6, '-');
+    assert_eq!(s.as_str(), "  ăb--");
+    assert_eq!(s.chars_count(), 6);
+    // already wide enough
+    s.pad_left(3, ' ');
+    s.center(6, '*');
+    assert_eq!(s.as_str(), "  ăb--");
+
+    let mut s = FlatString::<10>::from_str("ab");
+    s.center(5, '*');
+    assert_eq!(s.as_str(), "*ab**");
+    // capped at the capacity
+    s.center(20, '=');
+    assert_eq!(s.as_str(), "==*ab**===");
+    assert_eq!(s.len(), 10);
+    assert_eq!(s.chars_count(), 10);
+
+    // a multi-byte fill character is only added while it fits
+    let mut s = FlatString::<8>::from_str("ab");
+    s.pad_left(10, 'ă');
+    assert_eq!(s.as_str(), "ăăăab");
+    assert_eq!(s.chars_count(), 5);
+
+    let s = FlatString::<6>::from_str_padded("ab", '_');
+    assert_eq!(s.as_str(), "ab____");
+    let s = FlatString::<6>::from_str_padded("Hello World", '_');
+    assert_eq!(s.as_str(), "Hello ");
+    let s = FlatString::<6>::from_str_padded("a", 'ă');
+    assert_eq!(s.as_str(), "aăă");
+    assert_eq!(s.chars_count(), 3);
+}
+
+#[cfg(feature = "unicode-width")]
+#[test]
+fn check_padding_to_width() {
+    let mut s = FlatString::<20>::from_str("日本");
+    s.pad_right_to_width(5, '日');
+    assert_eq!(s.as_str(), "日本");
+    s.pad_right_to_width(6, '日');
+    assert_eq!(s.as_str(), "日本日");
+    s.pad_left_to_width(9, ' ');
+    assert_eq!(s.as_str(), "   日本日");
+    assert_eq!(s.display_width(), 9);
+    let mut s = FlatString::<20>::from_str("ab");
+    s.center_to_width(0, ' ');
+    s.center_to_width(4, '\u{301}');
+    assert_eq!(s.as_str(), "ab");
+    s.center_to_width(5, ' ');
+    assert_eq!(s.as_str(), " ab  ");
+}
+
+#[test]
+fn check_pop_n_and_truncate_chars() {
+    let mut s = FlatString::<32>::from_str("aă日👍b");
+    assert_eq!(s.pop(), Some('b'));
+    assert_eq!(s.pop(), Some('👍'));
+    assert_eq!(s.as_str(), "aă日");
+    assert_eq!(s.chars_count(), 3);
+    assert_eq!(s.pop_n(0), 0);
+    assert_eq!(s.pop_n(2), 2);
+    assert_eq!(s.as_str(), "a");
+    assert_eq!(s.pop_n(5), 1);
+    assert_eq!(s.pop_n(5), 0);
+    assert_eq!(s.pop(), None);
+
+    let mut s = FlatString::<32>::from_str("Hello World");
+    assert_eq!(s.pop_n(6), 6);
+    assert_eq!(s.as_str(), "Hello");
+    assert_eq!(s.chars_count(), 5);
+
+    for count in 0..8 {
+        let mut s = FlatString::<32>::from_str("aă日👍bcd");
+        s.truncate_chars(count);
+        let expected: String = "aă日👍bcd".chars().take(count).collect();
+        assert_eq!(s.as_str(), expected);
+        assert_eq!(s.chars_count(), count.min(7));
+    }
+}
+
+#[test]
+fn check_char_index_api() {
+    let text = "aă日👍b";
+    let s = FlatString::<32>::from_str(text);
+    let positions: Vec<usize> = text.char_indices().map(|(i, _)| i).chain([text.len()]).collect();
+    for (char_idx, byte_idx) in positions.iter().enumerate() {
+        assert_eq!(s.char_to_byte(char_idx), Some(*byte_idx));
+        assert_eq!(s.byte_to_char(*byte_idx), Some(char_idx));
+        assert_eq!(s.char_at(char_idx), text.chars().nth(char_idx));
+    }
+    assert_eq!(s.char_to_byte(6), None);
+    assert_eq!(s.byte_to_char(2), None);
+    assert_eq!(s.byte_to_char(20), None);
+
+    // ASCII fast path
+    let s = FlatString::<16>::from_str("Hello");
+    assert_eq!(s.char_to_byte(5), Some(5));
+    assert_eq!(s.char_to_byte(6), None);
+    assert_eq!(s.byte_to_char(4), Some(4));
+    assert_eq!(s.char_at(4), Some('o'));
+    assert_eq!(s.char_at(5), None);
+
+    let mut s = FlatString::<16>::from_str("aă日");
+    s.insert_at_char(3, "b");
+    s.insert_at_char(0, "👍");
+    assert_eq!(s.as_str(), "👍aă日b");
+    assert_eq!(s.chars_count(), 5);
+    assert_eq!(s.remove_at_char(3), '日');
+    assert_eq!(s.remove_at_char(0), '👍');
+    assert_eq!(s.as_str(), "aăb");
+    assert_eq!(s.chars_count(), 3);
+}
+
+#[test]
+#[should_panic]
+fn check_remove_at_char_out_of_range() {
+    let mut s = FlatString::<16>::from_str("aă");
+    s.remove_at_char(2);
+}
+
+#[test]
+fn check_insert_at_char_splitting_a_char() {
+    // the inserted text overwrites the string and ends inside the second '日', which is dropped
+    let mut s = FlatString::<6>::from_str("日日");
+    s.insert_at_char(0, "X日日");
+    assert_eq!(s.as_str(), "X日");
+    assert_eq!(s.chars_count(), 2);
+    let mut s = FlatString::<6>::from_str("日日");
+    s.insert_at_char(1, "ab");
+    assert_eq!(s.as_str(), "日ab");
+}
+
+#[test]
+#[should_panic]
+fn check_insert_at_char_out_of_range() {
+    let mut s = FlatString::<16>::from_str("aă");
+    s.insert_at_char(3, "b");
+}
+
+#[test]
+fn check_deref_mut() {
+    let mut s = FlatString::<16>::from_str("zăpadă Mare");
+    s.make_ascii_uppercase();
+    assert_eq!(s.as_str(), "ZăPADă MARE");
+    s.as_mut_str().make_ascii_lowercase();
+    assert_eq!(s.as_str(), "zăpadă mare");
+    assert_eq!(s.chars_count(), 11);
+    assert_eq!(s.len(), 13);
+    // the buffer after the end of the string is not exposed
+    assert_eq!(s.as_mut_str().len(), 13);
+}
+
+#[test]
+fn check_case_conversion() {
+    let s = FlatString::<16>::from_str("Hello World");
+    assert_eq!(s.to_uppercase().as_str(), "HELLO WORLD");
+    assert_eq!(s.to_lowercase().as_str(), "hello world");
+
+    let mut s = FlatString::<16>::from_str("Zăpadă ß");
+    s.make_uppercase();
+    assert_eq!(s.as_str(), "ZĂPADĂ SS");
+    assert_eq!(s.chars_count(), 9);
+    s.make_lowercase();
+    assert_eq!(s.as_str(), "zăpadă ss");
+
+    // the result does not fit in the capacity ('ŉ' is mapped to 'ʼ' followed by 'N')
+    let mut s = FlatString::<4>::from_str("ŉŉ");
+    let e = s.try_to_uppercase().unwrap_err();
+    assert_eq!(e.needed(), 6);
+    assert_eq!(e.available(), 4);
+    assert!(s.try_make_uppercase().is_err());
+    assert_eq!(s.as_str(), "ŉŉ");
+    s.make_uppercase();
+    assert_eq!(s.as_str(), "ʼN");
+    assert_eq!(s.chars_count(), 2);
+    assert!(s.try_make_lowercase().is_ok());
+    assert_eq!(s.as_str(), "ʼn");
+    // lowercase mappings can be longer too ('İ' is mapped to 'i' followed by a combining dot)
+    let s = FlatString::<4>::from_str("İİ");
+    assert_eq!(s.try_to_lowercase().unwrap_err().needed(), 6);
+    assert_eq!(s.to_lowercase().as_str(), "i\u{307}i");
+    assert_eq!(s.to_lowercase().chars_count(), 3);
+}
+
+#[test]
+fn check_case_conversion_in_place() {
+    // every character keeps its length, so a full string is converted in place
+    let mut s = FlatString::<12>::from_str("ZĂPADĂ ȘI");
+    assert_eq!(s.len(), 12);
+    s.make_lowercase();
+    assert_eq!(s.as_str(), "zăpadă și");
+    assert_eq!(s.chars_count(), 9);
+    assert!(s.try_make_uppercase().is_ok());
+    assert_eq!(s.as_str(), "ZĂPADĂ ȘI");
+    // 'ẞ' (3 bytes) is mapped to 'ß' (2 bytes), so the string is rebuilt
+    let mut s = FlatString::<8>::from_str("ẞA");
+    s.make_lowercase();
+    assert_eq!(s.as_str(), "ßa");
+    assert_eq!(s.len(), 3);
+    assert_eq!(s.chars_count(), 2);
+}
+
+#[test]
+fn check_trim_in_place() {
+    let mut s = FlatString::<32>::from_str("\u{3000} zăpadă\t \n");
+    s.trim_end_in_place();
+    assert_eq!(s.as_str(), "\u{3000} zăpadă");
+    assert_eq!(s.chars_count(), 8);
+    s.trim_start_in_place();
+    assert_eq!(s.as_str(), "zăpadă");
+    assert_eq!(s.chars_count(), 6);
+    assert_eq!(s.len(), 8);
+    s.trim_in_place();
+    assert_eq!(s.as_str(), "zăpadă");
+
+    let mut s = FlatString::<8>::from_str("  \t ");
+    s.trim_in_place();
+    assert!(s.is_empty());
+    assert_eq!(s.chars_count(), 0);
+}
+
+#[test]
+fn check_retain() {
+    let mut s = FlatString::<32>::from_str("a-ă-日-👍");
+    s.retain(|c| c != '-');
+    assert_eq!(s.as_str(), "aă日👍");
+    assert_eq!(s.chars_count(), 4);
+    let mut visited = Vec::new();
+    s.retain(|c| {
+        visited.push(c);
+        c.is_ascii()
+    });
+    assert_eq!(visited, ['a', 'ă', '日', '👍']);
+    assert_eq!(s.as_str(), "a");
+    s.retain(|_| false);
+    assert!(s.is_empty());
+    assert_eq!(s.chars_count(), 0);
+}
+
+#[test]
+fn check_retain_panic() {
+    let mut s = FlatString::<32>::from_str("aă-b-c");
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        s.retain(|c| {
+            if c == 'b' {
+                std::panic!("stop");
+            }
+            c != '-'
+        })
+    }));
+    assert!(result.is_err());
+    // the characters that were not visited are kept
+    assert_eq!(s.as_str(), "aăb-c");
+    assert_eq!(s.chars_count(), 5);
+}
+
+#[test]
+fn check_collapse_whitespace() {
+    let mut s = FlatString::<32>::from_str("\t a \u{3000}\u{3000} ă\n\nb  ");
+    s.collapse_whitespace();
+    assert_eq!(s.as_str(), " a ă b ");
+    assert_eq!(s.chars_count(), 7);
+    s.trim_in_place();
+    assert_eq!(s.as_str(), "a ă b");
+    s.collapse_whitespace();
+    assert_eq!(s.as_str(), "a ă b");
+    assert_eq!(s.chars_count(), 5);
+}
+
+#[cfg(feature = "unicode-segmentation")]
+#[test]
+fn check_push_str_graphemes_extends_content() {
+    // the combining accent extends the last grapheme cluster of the content when it fits
+    let mut s = FlatString::<4>::from_str("ae");
+    s.push_str_graphemes("\u{301}");
+    assert_eq!(s.as_str(), "ae\u{301}");
+    assert_eq!(s.graphemes_count(), 2);
+    // otherwise it is dropped, and the content is left as it was
+    let mut s = FlatString::<4>::from_str("abe");
+    s.push_str_graphemes("\u{301}");
+    assert_eq!(s.as_str(), "abe");
+}
+
+#[test]
+fn check_char_index_api_with_stale_chars_count() {
+    // "ab" is replaced with "ä" through as_bytes_mut, so the string is not ASCII anymore but len == chars == 2
+    let mut s = FlatString::<8>::from_str("ab");
+    unsafe { s.as_mut_str().as_bytes_mut() }.copy_from_slice("ä".as_bytes());
+    assert_eq!(s.len(), s.chars_count());
+    assert_eq!(s.char_to_byte(1), None);
+    assert_eq!(s.char_to_byte(2), Some(2));
+    assert_eq!(s.char_at(1), None);
+    s.truncate_chars(1);
+    assert!(core::str::from_utf8(s.as_bytes()).is_ok());
+    assert_eq!(s.as_str(), "ä");
+    assert_eq!(s.pop_n(1), 1);
+    assert!(core::str::from_utf8(s.as_bytes()).is_ok());
+    assert_eq!(s.as_str(), "");
+}
+
+#[test]
+fn check_pop_and_trim_with_stale_chars_count() {
+    // "ä" is replaced with "ab" through as_bytes_mut, so the string has 2 characters but chars_count is 1
+    let mut s = FlatString::<8>::from_str("ä");
+    unsafe { s.as_mut_str().as_bytes_mut() }.copy_from_slice(b"ab");
+    assert_eq!(s.pop(), Some('b'));
+    assert_eq!(s.pop(), Some('a'));
+    assert_eq!(s.pop(), None);
+    assert_eq!(s.chars_count(), 0);
+
+    let mut s = FlatString::<8>::from_str("ä");
+    unsafe { s.as_mut_str().as_bytes_mut() }.copy_from_slice(b"  ");
+    s.trim_in_place();
+    assert_eq!(s.as_str(), "");
+    assert_eq!(s.chars_count(), 0);
+
+    let mut s = FlatString::<8>::from_str("ä");
+    unsafe { s.as_mut_str().as_bytes_mut() }.copy_from_slice(b"ab");
+    assert_eq!(s.remove(0), 'a');
+    assert_eq!(s.remove(0), 'b');
+    let mut s = FlatString::<8>::from_str("ä");
+    unsafe { s.as_mut_str().as_bytes_mut() }.copy_from_slice(b"ab");
+    s.retain(|_| false);
+    assert_eq!(s.chars_count(), 0);
+    let mut s = FlatString::<8>::from_str("ä");
+    unsafe { s.as_mut_str().as_bytes_mut() }.copy_from_slice(b"  ");
+    s.collapse_whitespace();
+    assert_eq!(s.as_str(), " ");
+    // setting a new content fixes the number of characters
+    s.set("ab");
+    assert_eq!(s.chars_count(), 2);
+}
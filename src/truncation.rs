@@ -0,0 +1,46 @@
+/// Describes how much of a string slice was written into a FlatString by a truncating operation
+/// (such as `push_str_with_report`, `set_with_report` or `insert_with_report`).
+///
+/// Since a string slice is always written from its beginning, the bytes that were not written are the ones
+/// starting from `written` (always a char boundary, use [`Truncation::remainder`] to obtain them).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Truncation {
+    /// The number of bytes from the string slice that were written (the first `written` bytes).
+    pub written: usize,
+    /// The number of bytes from the string slice that were dropped because they did not fit.
+    pub dropped: usize,
+}
+impl Truncation {
+    pub(crate) fn new(text_len: usize, written: usize) -> Self {
+        Self {
+            written,
+            dropped: text_len - written,
+        }
+    }
+
+    /// Returns true if a part of the string slice was dropped, false if it was entirely written.
+    #[inline(always)]
+    pub fn is_truncated(&self) -> bool {
+        self.dropped > 0
+    }
+
+    /// Returns the part of the string slice that was not written.
+    ///
+    /// # Panics
+    /// Panics if `text` is not the string slice this report was created for.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let text = "Hello World !";
+    /// let mut first = FlatString::<8>::new();
+    /// let report = first.push_str_with_report(text);
+    /// let second = FlatString::<8>::from_str(report.remainder(text));
+    /// assert_eq!(first.as_str(), "Hello Wo");
+    /// assert_eq!(second.as_str(), "rld !");
+    /// ```
+    #[inline(always)]
+    pub fn remainder<'a>(&self, text: &'a str) -> &'a str {
+        &text[self.written..]
+    }
+}
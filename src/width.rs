@@ -0,0 +1,123 @@
+use crate::{FlatString, Length};
+use unicode_width::UnicodeWidthChar;
+
+// the number of terminal columns used by a character (control characters do not use any column)
+#[inline(always)]
+fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+impl<const SIZE: usize, L: Length> FlatString<SIZE, L> {
+    /// Returns the number of columns the string takes when displayed in a terminal, based on the East Asian Width
+    /// of its characters (most CJK characters and emojis take two columns, combining marks and control characters
+    /// take none). This is the sum of the widths of the characters, and is computed in O(n) time.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let s = FlatString::<20>::from_str("ab日本");
+    /// assert_eq!(s.chars_count(), 4);
+    /// assert_eq!(s.display_width(), 6);
+    /// ```
+    pub fn display_width(&self) -> usize {
+        self.as_str().chars().map(char_width).sum()
+    }
+
+    /// Truncates the string so that it takes at most `cols` columns when displayed in a terminal (a character that
+    /// takes two columns is never split). If the string already fits, this has no effect.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<20>::from_str("日本語");
+    /// s.truncate_to_width(5);
+    /// assert_eq!(s.as_str(), "日本");
+    /// assert_eq!(s.display_width(), 4);
+    /// ```
+    pub fn truncate_to_width(&mut self, cols: usize) {
+        let mut width = 0;
+        for (idx, c) in self.as_str().char_indices() {
+            width += char_width(c);
+            if width > cols {
+                self.truncate(idx);
+                return;
+            }
+        }
+    }
+
+    /// Appends the first characters of a string slice that fit both in the available space and in `cols` columns
+    /// (the display width of the whole string, including its current content, will be at most `cols`).
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<32>::from_str("> ");
+    /// s.push_str_fitting_width("こんにちは", 7);
+    /// assert_eq!(s.as_str(), "> こん");
+    /// assert_eq!(s.display_width(), 6);
+    /// ```
+    pub fn push_str_fitting_width(&mut self, text: &str, cols: usize) {
+        let available = SIZE - self.len();
+        let mut width = self.display_width();
+        let mut end = 0;
+        for (idx, c) in text.char_indices() {
+            width += char_width(c);
+            if width > cols || idx + c.len_utf8() > available {
+                break;
+            }
+            end = idx + c.len_utf8();
+        }
+        self.add_entire_string(&text[..end]);
+    }
+
+    /// Adds fill characters at the beginning of the string until it takes `cols` columns when displayed in a terminal
+    /// (aligning the text to the right). If the fill character takes two columns and one column remains, it is left
+    /// empty. The string never grows past its capacity (only the fill characters that fit are added).
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<20>::from_str("日本");
+    /// s.pad_left_to_width(6, ' ');
+    /// assert_eq!(s.as_str(), "  日本");
+    /// ```
+    pub fn pad_left_to_width(&mut self, cols: usize, fill: char) {
+        self.pad(self.fill_count(cols, fill), fill, true, false);
+    }
+
+    /// Adds fill characters at the end of the string until it takes `cols` columns when displayed in a terminal
+    /// (aligning the text to the left). The string never grows past its capacity.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<20>::from_str("日本");
+    /// s.pad_right_to_width(6, '.');
+    /// assert_eq!(s.as_str(), "日本..");
+    /// ```
+    pub fn pad_right_to_width(&mut self, cols: usize, fill: char) {
+        self.pad(self.fill_count(cols, fill), fill, false, true);
+    }
+
+    /// Adds fill characters on both sides of the string until it takes `cols` columns when displayed in a terminal
+    /// (centering the text, the extra fill character goes at the end). The string never grows past its capacity.
+    ///
+    /// # Example
+    /// ```rust
+    /// use flat_string::FlatString;
+    /// let mut s = FlatString::<20>::from_str("日本");
+    /// s.center_to_width(7, '-');
+    /// assert_eq!(s.as_str(), "-日本--");
+    /// ```
+    pub fn center_to_width(&mut self, cols: usize, fill: char) {
+        self.pad(self.fill_count(cols, fill), fill, true, true);
+    }
+
+    // the number of fill characters needed for the string to take `cols` columns
+    fn fill_count(&self, cols: usize, fill: char) -> usize {
+        match char_width(fill) {
+            0 => 0,
+            w => cols.saturating_sub(self.display_width()) / w,
+        }
+    }
+}